@@ -0,0 +1,130 @@
+use crc::{Crc, Digest, CRC_16_IBM_3740};
+
+/// CRC-16-CCITT as used by [`Command::ChecksumPages`](crate::command::Command::ChecksumPages).
+///
+/// Polynomial `0x1021`, initial value `0xFFFF`, no reflection and no final
+/// XOR, also known as CRC-16/CCITT-FALSE or CRC-16/IBM-3740. The check value
+/// of `"123456789"` is `0x29B1`.
+const CRC16_CCITT: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
+
+/// Compute the CRC-16-CCITT checksum of `data`.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::checksum::crc16_ccitt;
+/// assert_eq!(crc16_ccitt(b"123456789"), 0x29B1);
+/// ```
+pub fn crc16_ccitt(data: &[u8]) -> u16 {
+    CRC16_CCITT.checksum(data)
+}
+
+/// Streaming CRC-16-CCITT.
+///
+/// Computes the same checksum as [`crc16_ccitt`] over data fed in chunks, so
+/// the data doesn't need to be retained.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::checksum::{crc16_ccitt, Crc16};
+/// let mut crc = Crc16::new();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.finalize(), crc16_ccitt(b"123456789"));
+/// ```
+#[derive(Clone)]
+pub struct Crc16 {
+    digest: Digest<'static, u16>,
+}
+
+impl Crc16 {
+    /// Create a new checksum over no data.
+    pub fn new() -> Self {
+        Self {
+            digest: CRC16_CCITT.digest(),
+        }
+    }
+
+    /// Feed `data` into the checksum.
+    pub fn update(&mut self, data: &[u8]) {
+        self.digest.update(data);
+    }
+
+    /// Returns the checksum of all data fed so far.
+    pub fn finalize(self) -> u16 {
+        self.digest.finalize()
+    }
+}
+
+impl Default for Crc16 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for Crc16 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Crc16")
+            .field("value", &self.clone().finalize())
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for Crc16 {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Crc16 {{ value: {=u16:#06x} }}", self.clone().finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc16_ccitt() {
+        assert_eq!(crc16_ccitt(b""), 0xFFFF);
+        assert_eq!(crc16_ccitt(b"123456789"), 0x29B1);
+        assert_eq!(crc16_ccitt(b"A"), 0xB915);
+    }
+
+    #[test]
+    fn test_crc16_ccitt_bitwise() {
+        // reference bit-by-bit implementation
+        fn reference(data: &[u8]) -> u16 {
+            let mut crc: u16 = 0xFFFF;
+            for &byte in data {
+                crc ^= (byte as u16) << 8;
+                for _ in 0..8 {
+                    crc = if crc & 0x8000 != 0 {
+                        (crc << 1) ^ 0x1021
+                    } else {
+                        crc << 1
+                    };
+                }
+            }
+            crc
+        }
+
+        let page: [u8; 256] = core::array::from_fn(|i| i as u8);
+        assert_eq!(crc16_ccitt(&page), reference(&page));
+        assert_eq!(crc16_ccitt(&[0xFF; 256]), reference(&[0xFF; 256]));
+    }
+
+    #[test]
+    fn test_crc16_streaming() {
+        let data: [u8; 1000] = core::array::from_fn(|i| (i * 7) as u8);
+        let expected = crc16_ccitt(&data);
+
+        for chunk_len in [1, 3, 63, 256, 1000] {
+            let mut crc = Crc16::new();
+            for chunk in data.chunks(chunk_len) {
+                crc.update(chunk);
+            }
+            assert_eq!(crc.finalize(), expected);
+        }
+
+        assert_eq!(Crc16::default().finalize(), crc16_ccitt(b""));
+    }
+}
@@ -0,0 +1,378 @@
+//! Command message encoding into and decoding from HID reports.
+
+use crate::command::{Command, Request};
+use crate::output::{OutputCollector, OutputError};
+use crate::reassembly::{Reassembler, ReassemblyError};
+use crate::{wire_size, Packet, PacketError, PacketKind};
+
+/// Encode error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum EncodeError {
+    /// Remaining buffer is too small to fit the encoded reports.
+    BufferTooSmall { needed: usize, got: usize },
+}
+
+/// Command encoder.
+///
+/// Fragments commands into packets and writes them as zero-padded
+/// [`Packet::REPORT_LEN`] byte reports one after the other into a buffer.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::codec::Encoder;
+/// # use hftwo::command::Command;
+/// # use hftwo::{Packet, PacketKind};
+/// let mut buf = [0; 128];
+/// let mut encoder = Encoder::new(&mut buf);
+/// assert_eq!(encoder.push_command(Command::Info, 1, &[]), Ok(64));
+///
+/// let packet = Packet::iter(encoder.as_bytes()).next().unwrap();
+/// assert_eq!(packet.kind(), PacketKind::CommandFinal);
+/// assert_eq!(packet.data(), &[0x02, 0, 0, 0, 0x01, 0, 0, 0]);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Encoder<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> Encoder<'a> {
+    /// Create a new encoder writing into `buf`.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Encode a command request.
+    ///
+    /// Returns the number of bytes written, always a multiple of
+    /// [`Packet::REPORT_LEN`]. Nothing is written on error.
+    pub fn push_command(
+        &mut self,
+        command: Command,
+        tag: u16,
+        data: &[u8],
+    ) -> Result<usize, EncodeError> {
+        let message_len = Request::HEADER_LEN + data.len();
+        let needed = wire_size(message_len);
+        let got = self.buf.len() - self.pos;
+
+        if needed > got {
+            return Err(EncodeError::BufferTooSmall { needed, got });
+        }
+
+        // the request header only ever lands in the first packet, the rest
+        // of the data is fragmented as is
+        let (first, rest) = data.split_at(data.len().min(Packet::MAX_LEN - Request::HEADER_LEN));
+        let mut head = [0; Packet::MAX_LEN];
+        Request::write_header(&mut head, command, tag);
+        head[Request::HEADER_LEN..][..first.len()].copy_from_slice(first);
+        let head = &head[..Request::HEADER_LEN + first.len()];
+
+        let (first_report, rest_reports) =
+            self.buf[self.pos..][..needed].split_at_mut(Packet::REPORT_LEN);
+
+        first_report.fill(0);
+        if rest.is_empty() {
+            Packet::new(first_report, PacketKind::CommandFinal, head);
+        } else {
+            Packet::new(first_report, PacketKind::CommandInner, head);
+
+            let mut fragments = Packet::fragments(rest);
+            for report in rest_reports.chunks_exact_mut(Packet::REPORT_LEN) {
+                report.fill(0);
+                fragments.next(report);
+            }
+        }
+
+        self.pos += needed;
+        Ok(needed)
+    }
+
+    /// Returns the reports written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.pos]
+    }
+
+    /// Number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns `true` if nothing has been written.
+    pub fn is_empty(&self) -> bool {
+        self.pos == 0
+    }
+
+    /// Discard everything written so far.
+    pub fn reset(&mut self) {
+        self.pos = 0;
+    }
+}
+
+/// Decode error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum DecodeError {
+    /// Report does not hold a valid packet.
+    Packet(PacketError),
+    /// Command message does not fit in the scratch buffer.
+    Reassembly(ReassemblyError),
+    /// Output does not fit in its buffer.
+    Output(OutputError),
+}
+
+impl From<PacketError> for DecodeError {
+    fn from(value: PacketError) -> Self {
+        Self::Packet(value)
+    }
+}
+
+impl From<ReassemblyError> for DecodeError {
+    fn from(value: ReassemblyError) -> Self {
+        Self::Reassembly(value)
+    }
+}
+
+impl From<OutputError> for DecodeError {
+    fn from(value: OutputError) -> Self {
+        Self::Output(value)
+    }
+}
+
+/// Command decoder.
+///
+/// Reassembles command messages from a stream of reports while routing
+/// [`PacketKind::StdOut`] and [`PacketKind::StdErr`] payloads into an
+/// [`OutputCollector`].
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::codec::Decoder;
+/// let mut scratch = [0; 256];
+/// let mut stdout = [0; 64];
+/// let mut stderr = [0; 64];
+/// let mut decoder = Decoder::new(&mut scratch, &mut stdout, &mut stderr);
+///
+/// assert_eq!(decoder.push_report(&[0x82, b'h', b'i']), Ok(None));
+/// assert_eq!(decoder.push_report(&[0x02, 0x01, 0x02]), Ok(None));
+/// assert_eq!(decoder.push_report(&[0x41, 0x03]), Ok(Some(&[1, 2, 3][..])));
+/// assert_eq!(decoder.output().stdout(), b"hi");
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Decoder<'a> {
+    reassembler: Reassembler<'a>,
+    output: OutputCollector<'a>,
+}
+
+impl<'a> Decoder<'a> {
+    /// Create a new decoder.
+    ///
+    /// Command messages are reassembled in `scratch`, output is collected in
+    /// `stdout` and `stderr`.
+    pub fn new(scratch: &'a mut [u8], stdout: &'a mut [u8], stderr: &'a mut [u8]) -> Self {
+        Self {
+            reassembler: Reassembler::new(scratch),
+            output: OutputCollector::new(stdout, stderr),
+        }
+    }
+
+    /// Push a received report.
+    ///
+    /// Returns the full command message once its
+    /// [`PacketKind::CommandFinal`] packet is received. Any bytes following
+    /// the packet in the report are ignored.
+    pub fn push_report(&mut self, report: &[u8]) -> Result<Option<&[u8]>, DecodeError> {
+        let packet = Packet::try_from_bytes(report)?;
+
+        match packet.kind() {
+            PacketKind::CommandInner | PacketKind::CommandFinal => {
+                Ok(self.reassembler.push(&packet)?)
+            }
+            PacketKind::StdOut | PacketKind::StdErr => {
+                self.output.push(&packet)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the collected output.
+    pub fn output(&self) -> &OutputCollector<'a> {
+        &self.output
+    }
+
+    /// Mutably access the collected output, e.g. to clear it.
+    pub fn output_mut(&mut self) -> &mut OutputCollector<'a> {
+        &mut self.output
+    }
+
+    /// Discard any partial command message.
+    pub fn reset(&mut self) {
+        self.reassembler.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_single_report() {
+        let mut buf = [0xFF; 64];
+        let mut encoder = Encoder::new(&mut buf);
+        assert_eq!(encoder.push_command(Command::Info, 0x1234, &[0xAA]), Ok(64));
+
+        let bytes = encoder.as_bytes();
+        assert_eq!(
+            &bytes[..10],
+            &[0x49, 0x02, 0x00, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00, 0xAA]
+        );
+        assert!(bytes[10..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_encode_multi_report() {
+        let data: [u8; 100] = core::array::from_fn(|i| i as u8);
+
+        let mut buf = [0xFF; 256];
+        let mut encoder = Encoder::new(&mut buf);
+        assert_eq!(encoder.push_command(Command::WriteWords, 7, &data), Ok(128));
+        assert_eq!(encoder.push_command(Command::Dmesg, 8, &[]), Ok(64));
+        assert_eq!(encoder.len(), 192);
+
+        let mut packets = Packet::iter(encoder.as_bytes());
+        let mut storage = [0; 128];
+        let mut reassembler = Reassembler::new(&mut storage);
+
+        let first = packets.next().unwrap();
+        assert_eq!(first.kind(), PacketKind::CommandInner);
+        assert_eq!(first.payload_len(), Packet::MAX_LEN);
+        assert_eq!(reassembler.push(&first), Ok(None));
+
+        let second = packets.next().unwrap();
+        assert_eq!(second.kind(), PacketKind::CommandFinal);
+        assert_eq!(second.payload_len(), 108 - Packet::MAX_LEN);
+
+        let message = reassembler.push(&second).unwrap().unwrap();
+        let request = Request::from_bytes(message);
+        assert_eq!(request.command(), Command::WriteWords);
+        assert_eq!(request.tag(), 7);
+        assert_eq!(request.data(), &data);
+
+        let third = packets.next().unwrap();
+        assert_eq!(third.kind(), PacketKind::CommandFinal);
+        assert!(packets.next().is_none());
+    }
+
+    #[test]
+    fn test_encode_boundary() {
+        let data = [0xAA; 56];
+
+        let mut buf = [0; 192];
+        let mut encoder = Encoder::new(&mut buf);
+        assert_eq!(
+            encoder.push_command(Command::WriteWords, 1, &data[..55]),
+            Ok(64)
+        );
+        assert_eq!(encoder.push_command(Command::WriteWords, 2, &data), Ok(128));
+
+        let mut packets = Packet::iter(encoder.as_bytes());
+
+        let packet = packets.next().unwrap();
+        assert_eq!(packet.kind(), PacketKind::CommandFinal);
+        assert_eq!(packet.payload_len(), Packet::MAX_LEN);
+
+        let packet = packets.next().unwrap();
+        assert_eq!(packet.kind(), PacketKind::CommandInner);
+        assert_eq!(packet.payload_len(), Packet::MAX_LEN);
+        assert_eq!(
+            &packet.data()[..Request::HEADER_LEN],
+            &[9, 0, 0, 0, 2, 0, 0, 0]
+        );
+
+        let packet = packets.next().unwrap();
+        assert_eq!(packet.kind(), PacketKind::CommandFinal);
+        assert_eq!(packet.data(), &[0xAA]);
+        assert!(packets.next().is_none());
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf = [0; 100];
+        let mut encoder = Encoder::new(&mut buf);
+        assert_eq!(encoder.push_command(Command::Info, 1, &[]), Ok(64));
+        assert_eq!(
+            encoder.push_command(Command::Info, 2, &[]),
+            Err(EncodeError::BufferTooSmall {
+                needed: 64,
+                got: 36
+            })
+        );
+        assert_eq!(encoder.len(), 64);
+
+        encoder.reset();
+        assert!(encoder.is_empty());
+    }
+
+    #[test]
+    fn test_decode_two_reports() {
+        let data: [u8; 100] = core::array::from_fn(|i| i as u8);
+
+        let mut buf = [0; 128];
+        let mut encoder = Encoder::new(&mut buf);
+        encoder.push_command(Command::WriteWords, 3, &data).unwrap();
+
+        let mut scratch = [0; 128];
+        let mut stdout = [0; 16];
+        let mut stderr = [0; 16];
+        let mut decoder = Decoder::new(&mut scratch, &mut stdout, &mut stderr);
+
+        let (first, second) = encoder.as_bytes().split_at(Packet::REPORT_LEN);
+        assert_eq!(decoder.push_report(first), Ok(None));
+        assert_eq!(decoder.push_report(&[0x83, b'l', b'o', b'g']), Ok(None));
+        assert_eq!(decoder.push_report(&[0xC1, b'!']), Ok(None));
+
+        let message = decoder.push_report(second).unwrap().unwrap();
+        let request = Request::from_bytes(message);
+        assert_eq!(request.command(), Command::WriteWords);
+        assert_eq!(request.tag(), 3);
+        assert_eq!(request.data(), &data);
+
+        assert_eq!(decoder.output().stdout(), b"log");
+        assert_eq!(decoder.output().stderr(), b"!");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut scratch = [0; 4];
+        let mut stdout = [0; 1];
+        let mut stderr = [0; 1];
+        let mut decoder = Decoder::new(&mut scratch, &mut stdout, &mut stderr);
+
+        assert_eq!(
+            decoder.push_report(&[0x45, 0x01]),
+            Err(DecodeError::Packet(PacketError::Truncated {
+                len: 6,
+                available: 2
+            }))
+        );
+        assert_eq!(
+            decoder.push_report(&[0x45, 1, 2, 3, 4, 5]),
+            Err(DecodeError::Reassembly(ReassemblyError::Overflow {
+                needed: 5,
+                capacity: 4
+            }))
+        );
+        assert_eq!(
+            decoder.push_report(&[0x82, b'a', b'b']),
+            Err(DecodeError::Output(OutputError::Overflow {
+                needed: 2,
+                capacity: 1
+            }))
+        );
+    }
+}
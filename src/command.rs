@@ -1,11 +1,24 @@
-use crate::{Packet, PacketKind};
+pub mod bininfo;
+pub mod dmesg;
+pub mod flash;
+pub mod info;
+#[cfg(feature = "alloc")]
+mod owned;
+mod words;
+
+pub use flash::{ChecksumPagesRequest, ChecksumPagesResponse};
+#[cfg(feature = "alloc")]
+pub use owned::{OwnedRequest, OwnedResponse};
+pub use words::{parse_read_words, ReadWordsRequest, ReadWordsResponse, WriteWordsRequest};
+
+use crate::{le, Packet, PacketFragments, PacketIter, PacketKind, Stride};
 
 /// Commands.
 ///
 /// Specifies the commands in the spec as well as `Other` for user-defined
 /// commands.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum Command {
     BinInfo = 0x0001,
@@ -21,6 +34,107 @@ pub enum Command {
     Other(u32),
 }
 
+impl Command {
+    /// Every command defined by the spec, in ID order.
+    pub const ALL: &'static [Command] = &[
+        Self::BinInfo,
+        Self::Info,
+        Self::ResetIntoApp,
+        Self::ResetIntoBootloader,
+        Self::StartFlash,
+        Self::WriteFlashPage,
+        Self::ChecksumPages,
+        Self::ReadWords,
+        Self::WriteWords,
+        Self::Dmesg,
+    ];
+
+    /// Returns the name of the command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BinInfo => "BinInfo",
+            Self::Info => "Info",
+            Self::ResetIntoApp => "ResetIntoApp",
+            Self::ResetIntoBootloader => "ResetIntoBootloader",
+            Self::StartFlash => "StartFlash",
+            Self::WriteFlashPage => "WriteFlashPage",
+            Self::ChecksumPages => "ChecksumPages",
+            Self::ReadWords => "ReadWords",
+            Self::WriteWords => "WriteWords",
+            Self::Dmesg => "Dmesg",
+            Self::Other(_) => "Other",
+        }
+    }
+
+    /// Strict conversion that only accepts standard command IDs.
+    ///
+    /// Use [`Command::from`] to map unknown IDs to [`Command::Other`]. This
+    /// is not a `TryFrom` impl as that is already provided through `From`.
+    pub fn try_from_standard(value: u32) -> Result<Self, UnknownCommand> {
+        match Self::from(value) {
+            Self::Other(value) => Err(UnknownCommand(value)),
+            command => Ok(command),
+        }
+    }
+
+    /// Returns `true` if the command is defined by the spec.
+    pub fn is_standard(&self) -> bool {
+        !matches!(self, Self::Other(_))
+    }
+
+    /// Returns the request data length expected by the spec.
+    ///
+    /// Returns [`ExpectedLen::Variable`] for [`Command::Other`] as their data
+    /// is device-defined.
+    pub fn expected_data_len(&self) -> ExpectedLen {
+        match self {
+            Self::BinInfo
+            | Self::Info
+            | Self::ResetIntoApp
+            | Self::ResetIntoBootloader
+            | Self::StartFlash
+            | Self::Dmesg => ExpectedLen::Exact(0),
+            Self::WriteFlashPage => ExpectedLen::Min(4),
+            Self::ChecksumPages | Self::ReadWords => ExpectedLen::Exact(8),
+            Self::WriteWords => ExpectedLen::Min(8),
+            Self::Other(_) => ExpectedLen::Variable,
+        }
+    }
+
+    /// Returns the category of the command.
+    pub fn category(&self) -> Category {
+        match self {
+            Self::ResetIntoApp | Self::ResetIntoBootloader => Category::Control,
+            Self::StartFlash | Self::WriteFlashPage | Self::ChecksumPages => Category::Flash,
+            Self::ReadWords | Self::WriteWords => Category::Memory,
+            Self::BinInfo | Self::Info | Self::Dmesg => Category::Info,
+            Self::Other(_) => Category::Other,
+        }
+    }
+
+    /// Returns the command ID as little-endian bytes, as it appears in the
+    /// request header.
+    pub const fn to_le_bytes(&self) -> [u8; 4] {
+        self.id().to_le_bytes()
+    }
+
+    const fn id(&self) -> u32 {
+        match self {
+            Self::BinInfo => 0x0001,
+            Self::Info => 0x0002,
+            Self::ResetIntoApp => 0x0003,
+            Self::ResetIntoBootloader => 0x0004,
+            Self::StartFlash => 0x0005,
+            Self::WriteFlashPage => 0x0006,
+            Self::ChecksumPages => 0x0007,
+            Self::ReadWords => 0x0008,
+            Self::WriteWords => 0x0009,
+            Self::Dmesg => 0x0010,
+            Self::Other(value) => *value,
+        }
+    }
+}
+
 impl From<u32> for Command {
     fn from(value: u32) -> Self {
         match value {
@@ -30,29 +144,93 @@ impl From<u32> for Command {
             0x0004 => Self::ResetIntoBootloader,
             0x0005 => Self::StartFlash,
             0x0006 => Self::WriteFlashPage,
-            0x0007 => Self::ReadWords,
-            0x0008 => Self::WriteWords,
+            0x0007 => Self::ChecksumPages,
+            0x0008 => Self::ReadWords,
+            0x0009 => Self::WriteWords,
             0x0010 => Self::Dmesg,
             _ => Self::Other(value),
         }
     }
 }
 
-impl Into<u32> for Command {
-    fn into(self) -> u32 {
+/// Same as [`Display`](core::fmt::Display), printing `Other` IDs in hex.
+impl core::fmt::Debug for Command {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for Command {
+    fn format(&self, f: defmt::Formatter) {
         match self {
-            Self::BinInfo => 0x0001,
-            Self::Info => 0x0002,
-            Self::ResetIntoApp => 0x0003,
-            Self::ResetIntoBootloader => 0x0004,
-            Self::StartFlash => 0x0005,
-            Self::WriteFlashPage => 0x0006,
-            Self::ChecksumPages => 0x0007,
-            Self::ReadWords => 0x0008,
-            Self::WriteWords => 0x0009,
-            Self::Dmesg => 0x0010,
-            Self::Other(value) => value,
+            Self::Other(value) => defmt::write!(f, "Other({=u32:#X})", value),
+            command => defmt::write!(f, "{=str}", command.name()),
+        }
+    }
+}
+
+impl core::fmt::Display for Command {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Other(value) => write!(f, "Other({:#X})", value),
+            command => f.write_str(command.name()),
+        }
+    }
+}
+
+/// Expected request data length of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ExpectedLen {
+    /// Exactly this many bytes.
+    Exact(usize),
+    /// At least this many bytes.
+    Min(usize),
+    /// Any length.
+    Variable,
+}
+
+/// Command category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Category {
+    /// Resetting the device.
+    Control,
+    /// Writing and verifying flash.
+    Flash,
+    /// Reading and writing memory words.
+    Memory,
+    /// Querying device information and logs.
+    Info,
+    /// User-defined commands.
+    Other,
+}
+
+/// Check the data length of a received request against
+/// [`Command::expected_data_len`].
+///
+/// Requests for [`Command::Other`] are always accepted.
+pub fn validate_request(request: &Request) -> Result<(), CommandError> {
+    let got = request.len();
+
+    match request.command().expected_data_len() {
+        ExpectedLen::Exact(needed) | ExpectedLen::Min(needed) if got < needed => {
+            Err(CommandError::DataTooShort { needed, got })
         }
+        ExpectedLen::Exact(max) if got > max => Err(CommandError::DataTooLong { max, got }),
+        _ => Ok(()),
+    }
+}
+
+/// Command ID not defined by the spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct UnknownCommand(pub u32);
+
+impl From<Command> for u32 {
+    fn from(value: Command) -> Self {
+        value.id()
     }
 }
 
@@ -61,22 +239,69 @@ impl Into<u32> for Command {
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub struct Request<'a>(&'a [u8]);
 
+/// Requests are equal if their command, tag and data are equal, regardless of
+/// the reserved header bytes. See [`Request::eq_bytes`] for an exact
+/// comparison.
+impl PartialEq for Request<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.command() == other.command()
+            && self.tag() == other.tag()
+            && self.data() == other.data()
+    }
+}
+
+impl Eq for Request<'_> {}
+
 impl<'a> Request<'a> {
     pub const HEADER_LEN: usize = 8;
 
     /// Creates a new [`Request`].
     ///
-    /// `buf` must be 8 bytes larger than `data` to fit the header.
+    /// `buf` must be at least 8 bytes larger than `data` to fit the header.
+    /// The request only occupies the front of an over-sized buffer.
+    ///
+    /// The reserved header bytes are zeroed.
     pub fn new(buf: &'a mut [u8], command: Command, tag: u16, data: &[u8]) -> Self {
+        Self::new_with_reserved(buf, command, tag, [0; 2], data)
+    }
+
+    /// Creates a new [`Request`], taking the buffer last.
+    ///
+    /// Same as [`Request::new`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hftwo::command::{Command, Request};
+    /// let mut buf = [0; 64];
+    /// let request = Request::from_command(Command::ReadWords, 2, &[0; 8], &mut buf);
+    /// assert_eq!(request.command(), Command::ReadWords);
+    /// assert_eq!(request.tag(), 2);
+    /// assert_eq!(request.len(), 8);
+    /// ```
+    pub fn from_command(command: Command, tag: u16, data: &[u8], buf: &'a mut [u8]) -> Self {
+        Self::new(buf, command, tag, data)
+    }
+
+    /// Creates a new [`Request`] with the reserved header bytes set.
+    ///
+    /// `buf` must be at least 8 bytes larger than `data` to fit the header.
+    pub fn new_with_reserved(
+        buf: &'a mut [u8],
+        command: Command,
+        tag: u16,
+        reserved: [u8; 2],
+        data: &[u8],
+    ) -> Self {
         // ensure header and data will fit in buffer
-        assert!(buf.len() == (data.len() + Self::HEADER_LEN));
+        let len = Self::HEADER_LEN + data.len();
+        assert!(buf.len() >= len);
+        let buf = &mut buf[..len];
 
-        // write command id
-        let cmd: u32 = command.into();
-        buf[0..4].copy_from_slice(&cmd.to_le_bytes());
+        Self::write_header(buf, command, tag);
 
-        // write tag
-        buf[4..6].copy_from_slice(&tag.to_le_bytes());
+        // write reserved
+        buf[6..8].copy_from_slice(&reserved);
 
         // write data
         buf[8..].copy_from_slice(data);
@@ -84,6 +309,71 @@ impl<'a> Request<'a> {
         Self(buf)
     }
 
+    /// Creates a new [`Request`] with the data concatenated from `parts` in
+    /// order.
+    ///
+    /// Avoids joining header fields and payload in an intermediate buffer.
+    /// `buf` must be at least 8 bytes larger than the parts combined.
+    pub fn new_parts(buf: &'a mut [u8], command: Command, tag: u16, parts: &[&[u8]]) -> Self {
+        // ensure header and data will fit in buffer
+        let len = Self::HEADER_LEN + parts.iter().map(|part| part.len()).sum::<usize>();
+        assert!(buf.len() >= len);
+        let buf = &mut buf[..len];
+
+        Self::write_header(buf, command, tag);
+
+        // write data
+        let mut offset = Self::HEADER_LEN;
+        for part in parts {
+            buf[offset..][..part.len()].copy_from_slice(part);
+            offset += part.len();
+        }
+
+        Self(buf)
+    }
+
+    /// Creates a new [`Command::ResetIntoApp`] request.
+    ///
+    /// `buf` must be at least 8 bytes to fit the header.
+    pub fn reset_into_app(buf: &'a mut [u8], tag: u16) -> Self {
+        Self::new(buf, Command::ResetIntoApp, tag, &[])
+    }
+
+    /// Creates a new [`Command::ResetIntoBootloader`] request.
+    ///
+    /// `buf` must be at least 8 bytes to fit the header.
+    pub fn reset_into_bootloader(buf: &'a mut [u8], tag: u16) -> Self {
+        Self::new(buf, Command::ResetIntoBootloader, tag, &[])
+    }
+
+    /// Creates a new [`Command::StartFlash`] request.
+    ///
+    /// `buf` must be at least 8 bytes to fit the header. The corresponding
+    /// [`Response`] carries only a status and no data.
+    pub fn start_flash(buf: &'a mut [u8], tag: u16) -> Self {
+        Self::new(buf, Command::StartFlash, tag, &[])
+    }
+
+    /// Write the header fields into the front of `buf`.
+    ///
+    /// The reserved header bytes are zeroed.
+    pub(crate) fn write_header(buf: &mut [u8], command: Command, tag: u16) {
+        // write command id
+        le::write_u32(buf, 0, command.into()).unwrap();
+
+        // write tag
+        le::write_u16(buf, 4, tag).unwrap();
+
+        // clear reserved
+        buf[6] = 0;
+        buf[7] = 0;
+    }
+
+    /// Creates a new [`RequestBuilder`].
+    pub fn builder() -> RequestBuilder<'static> {
+        RequestBuilder::new()
+    }
+
     /// Creates a new [`Request`] from a byte array.
     pub fn from_bytes(buf: &'a [u8]) -> Self {
         assert!(buf.len() >= Self::HEADER_LEN);
@@ -95,16 +385,40 @@ impl<'a> Request<'a> {
         self.0.len() - Self::HEADER_LEN
     }
 
+    /// Returns `true` if the request carries no data.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Length of the request including the header.
+    pub fn total_len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if both requests are byte-for-byte identical, including
+    /// the reserved header bytes.
+    pub fn eq_bytes(&self, other: &Request) -> bool {
+        self.0 == other.0
+    }
+
     /// Get command.
     pub fn command(&self) -> Command {
-        let bytes = &self.0[0..4];
-        Command::from(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        Command::from(self.command_id())
+    }
+
+    /// Get the raw command ID, without converting to [`Command`].
+    pub fn command_id(&self) -> u32 {
+        le::read_u32(self.0, 0).unwrap()
     }
 
     /// Get tag.
     pub fn tag(&self) -> u16 {
-        let bytes = &self.0[4..6];
-        u16::from_le_bytes([bytes[0], bytes[1]])
+        le::read_u16(self.0, 4).unwrap()
+    }
+
+    /// Get reserved header bytes.
+    pub fn reserved(&self) -> [u8; 2] {
+        [self.0[6], self.0[7]]
     }
 
     /// Command data.
@@ -113,11 +427,187 @@ impl<'a> Request<'a> {
     }
 
     /// Get a [`Packet`] iterator.
-    pub fn into_packet_iter(&self) -> RequestPacketIter {
+    pub fn into_packet_iter(&self) -> RequestPacketIter<'_> {
         RequestPacketIter {
-            request: self,
-            chunk: 0,
+            fragments: Packet::fragments(self.0),
+        }
+    }
+}
+
+/// Request error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum RequestError {
+    /// No command was set.
+    MissingCommand,
+    /// Buffer is too small to hold the header and data.
+    BufferTooSmall { needed: usize, got: usize },
+}
+
+/// Fluent [`Request`] builder.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{Command, Request};
+/// let mut buf = [0; 64];
+/// let request = Request::builder()
+///     .command(Command::Info)
+///     .tag(3)
+///     .data(&[1, 2])
+///     .build_into(&mut buf)
+///     .unwrap();
+/// assert_eq!(request.command(), Command::Info);
+/// assert_eq!(request.data(), &[1, 2]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct RequestBuilder<'d> {
+    command: Option<Command>,
+    tag: u16,
+    reserved: [u8; 2],
+    data: &'d [u8],
+}
+
+impl RequestBuilder<'static> {
+    /// Creates a new [`RequestBuilder`] with no command, a zero tag, zeroed
+    /// reserved bytes and no data.
+    pub fn new() -> Self {
+        Self {
+            command: None,
+            tag: 0,
+            reserved: [0; 2],
+            data: &[],
+        }
+    }
+}
+
+impl Default for RequestBuilder<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'d> RequestBuilder<'d> {
+    /// Set the command.
+    pub fn command(mut self, command: Command) -> Self {
+        self.command = Some(command);
+        self
+    }
+
+    /// Set the tag.
+    pub fn tag(mut self, tag: u16) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    /// Set the reserved header bytes.
+    pub fn reserved(mut self, reserved: [u8; 2]) -> Self {
+        self.reserved = reserved;
+        self
+    }
+
+    /// Set the data.
+    pub fn data<'e>(self, data: &'e [u8]) -> RequestBuilder<'e> {
+        RequestBuilder {
+            command: self.command,
+            tag: self.tag,
+            reserved: self.reserved,
+            data,
+        }
+    }
+
+    /// Write the request into the front of `buf`.
+    ///
+    /// Returns an error if no command was set or if `buf` is too small.
+    pub fn build_into<'a>(self, buf: &'a mut [u8]) -> Result<Request<'a>, RequestError> {
+        let command = self.command.ok_or(RequestError::MissingCommand)?;
+
+        let needed = Request::HEADER_LEN + self.data.len();
+        if buf.len() < needed {
+            return Err(RequestError::BufferTooSmall {
+                needed,
+                got: buf.len(),
+            });
+        }
+
+        Ok(Request::new_with_reserved(
+            &mut buf[..needed],
+            command,
+            self.tag,
+            self.reserved,
+            self.data,
+        ))
+    }
+}
+
+/// In-place [`Request`] writer.
+///
+/// Writes the header up front and hands out the rest of the buffer so the
+/// body can be assembled directly, without copying it from a separate
+/// buffer.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{Command, RequestWriter};
+/// let mut buf = [0; 64];
+/// let mut writer = RequestWriter::new(&mut buf, Command::WriteFlashPage, 1).unwrap();
+/// let body = writer.body_mut();
+/// body[..4].copy_from_slice(&0x2000u32.to_le_bytes());
+/// body[4..8].copy_from_slice(&[1, 2, 3, 4]);
+///
+/// let request = writer.finish(8).unwrap();
+/// assert_eq!(request.data(), &[0x00, 0x20, 0, 0, 1, 2, 3, 4]);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct RequestWriter<'a> {
+    buf: &'a mut [u8],
+}
+
+impl<'a> RequestWriter<'a> {
+    /// Write the request header into the front of `buf`.
+    ///
+    /// The reserved header bytes are zeroed. Returns an error if `buf` is
+    /// too small to hold the header.
+    pub fn new(buf: &'a mut [u8], command: Command, tag: u16) -> Result<Self, RequestError> {
+        if buf.len() < Request::HEADER_LEN {
+            return Err(RequestError::BufferTooSmall {
+                needed: Request::HEADER_LEN,
+                got: buf.len(),
+            });
         }
+
+        Request::write_header(buf, command, tag);
+
+        Ok(Self { buf })
+    }
+
+    /// Space available for the body.
+    pub fn capacity(&self) -> usize {
+        self.buf.len() - Request::HEADER_LEN
+    }
+
+    /// Mutably access the body, following the header.
+    pub fn body_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[Request::HEADER_LEN..]
+    }
+
+    /// Finish the request with the first `len` bytes of the body as its
+    /// data.
+    ///
+    /// Returns an error if `len` exceeds [`RequestWriter::capacity`].
+    pub fn finish(self, len: usize) -> Result<Request<'a>, RequestError> {
+        let needed = Request::HEADER_LEN + len;
+        if needed > self.buf.len() {
+            return Err(RequestError::BufferTooSmall {
+                needed,
+                got: self.buf.len(),
+            });
+        }
+
+        Ok(Request(&self.buf[..needed]))
     }
 }
 
@@ -128,52 +618,90 @@ impl<'a> Request<'a> {
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub struct RequestPacketIter<'a> {
-    request: &'a Request<'a>,
-    chunk: usize,
+    fragments: PacketFragments<'a>,
 }
 
 impl<'a> RequestPacketIter<'a> {
     /// Get the next packet using `buf` to store the packet data.
     pub fn next<'b>(&mut self, buf: &'b mut [u8]) -> Option<Packet<'b>> {
-        let chunks = self.request.0.chunks(Packet::MAX_LEN);
-
-        let chunk = match self.request.0.chunks(Packet::MAX_LEN).nth(self.chunk) {
-            Some(c) => c,
-            None => return None,
-        };
-
-        let kind = if chunks.count() - 1 == self.chunk {
-            PacketKind::CommandFinal
-        } else {
-            PacketKind::CommandInner
-        };
-
-        // increment to next chunk
-        self.chunk += 1;
-
-        Some(Packet::new(buf, kind, chunk))
+        self.fragments.next(buf)
     }
 }
 
+/// Command data error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum CommandError {
+    /// Request data is shorter than the command requires.
+    DataTooShort { needed: usize, got: usize },
+    /// Request data is longer than the command allows.
+    DataTooLong { max: usize, got: usize },
+}
+
 /// Response status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum Status {
     /// The command was processed successfully.
-    Sucess = 0x00,
+    Success = 0x00,
     /// Command ID was not known to the device.
     Unknown = 0x01,
     /// An error occurred during execution of the command.
     Error = 0x02,
     /// Any other status response.
+    ///
+    /// The meaning of these codes is device-defined, they are not necessarily
+    /// errors.
     Other(u8),
 }
 
+impl Status {
+    /// Misspelled alias of [`Status::Success`].
+    #[deprecated(note = "use `Status::Success` instead")]
+    #[allow(non_upper_case_globals)]
+    pub const Sucess: Status = Status::Success;
+
+    /// Returns `true` if the status is [`Status::Success`].
+    pub fn is_success(&self) -> bool {
+        *self == Self::Success
+    }
+
+    /// Returns `true` if the status is [`Status::Unknown`] or
+    /// [`Status::Error`].
+    ///
+    /// [`Status::Other`] codes are device-defined and not classified as
+    /// errors, so a status may be neither a success nor an error.
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Unknown | Self::Error)
+    }
+
+    /// Returns `true` for [`Status::Other`] codes `0x03..=0x0F`.
+    ///
+    /// Some implementations reserve these for future standard statuses, so
+    /// hosts may want to warn about them rather than treat them as
+    /// device-defined.
+    pub fn is_reserved(&self) -> bool {
+        matches!(self, Self::Other(0x03..=0x0F))
+    }
+}
+
+impl core::fmt::Display for Status {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Success => f.write_str("Success"),
+            Self::Unknown => f.write_str("Unknown"),
+            Self::Error => f.write_str("Error"),
+            Self::Other(value) => write!(f, "Other({})", value),
+        }
+    }
+}
+
 impl From<u8> for Status {
     fn from(value: u8) -> Self {
         match value {
-            0x00 => Self::Sucess,
+            0x00 => Self::Success,
             0x01 => Self::Unknown,
             0x02 => Self::Error,
             _ => Self::Other(value),
@@ -181,74 +709,380 @@ impl From<u8> for Status {
     }
 }
 
-impl Into<u8> for Status {
-    fn into(self) -> u8 {
-        match self {
-            Self::Sucess => 0x00,
-            Self::Unknown => 0x01,
-            Self::Error => 0x02,
-            Self::Other(value) => value,
+impl From<Status> for u8 {
+    fn from(value: Status) -> Self {
+        match value {
+            Status::Success => 0x00,
+            Status::Unknown => 0x01,
+            Status::Error => 0x02,
+            Status::Other(value) => value,
         }
     }
 }
 
-/// Command response.
-#[derive(Debug)]
+/// Response status info.
+///
+/// A command-specific sub-status accompanying the [`Status`]. By convention,
+/// for [`Status::Error`] it often carries an errno-like error code, and for
+/// [`Status::Success`] it is usually zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
-pub struct Response<'a>(&'a [u8]);
-
-impl<'a> Response<'a> {
-    pub const HEADER_LEN: usize = 4;
-
-    /// Creates a new [`Response`].
-    ///
-    /// `buf` must be 8 bytes larger than `data` to fit the header.
-    pub fn new(buf: &'a mut [u8], tag: u16, status: Status, status_info: u8, data: &[u8]) -> Self {
-        // ensure header and data will fit in buffer
-        assert!(buf.len() == data.len() + Self::HEADER_LEN);
+pub struct StatusInfo(pub u8);
 
-        buf[0..2].copy_from_slice(&tag.to_le_bytes());
-        buf[2] = status.into();
-        buf[3] = status_info;
-        buf[Self::HEADER_LEN..].copy_from_slice(data);
+impl StatusInfo {
+    /// No additional status info.
+    pub const NONE: Self = Self(0);
 
-        Self(buf)
+    /// Create a new status info.
+    pub const fn new(value: u8) -> Self {
+        Self(value)
     }
 
-    /// Creates a new [`Response`] from a byte array.
-    pub fn from_bytes(buf: &'a [u8]) -> Self {
-        assert!(buf.len() >= Self::HEADER_LEN);
-        Self(buf)
+    /// Returns the raw status info byte.
+    pub const fn get(self) -> u8 {
+        self.0
     }
+}
 
-    /// Returns the tag.
-    pub fn tag(&self) -> u16 {
-        let bytes = &self.0[0..2];
-        u16::from_le_bytes([bytes[0], bytes[1]])
+impl From<u8> for StatusInfo {
+    fn from(value: u8) -> Self {
+        Self(value)
     }
+}
 
-    /// Returns the status.
-    pub fn status(&self) -> Status {
-        Status::from(self.0[2])
+impl From<StatusInfo> for u8 {
+    fn from(value: StatusInfo) -> Self {
+        value.0
     }
+}
 
-    /// Returns the status info byte.
-    pub fn status_info(&self) -> u8 {
-        self.0[3]
-    }
+impl core::ops::Deref for StatusInfo {
+    type Target = u8;
 
-    /// Returns a slice containing the data.
-    pub fn data(&self) -> &[u8] {
-        &self.0[Self::HEADER_LEN..]
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl PartialEq<u8> for StatusInfo {
+    fn eq(&self, other: &u8) -> bool {
+        self.0 == *other
+    }
+}
 
-    #[test]
-    fn test_command() {
+/// Response status error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum StatusError {
+    /// Command ID was not known to the device.
+    Unknown { status_info: u8 },
+    /// An error occurred during execution of the command.
+    Error { status_info: u8 },
+    /// Any other status response.
+    Other { status: u8, status_info: u8 },
+}
+
+/// Response error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ResponseError {
+    /// Buffer is too small to hold the header and data.
+    BufferTooSmall { needed: usize, got: usize },
+    /// Buffer is shorter than the header.
+    Truncated { len: usize },
+}
+
+/// Response tag does not match the request tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct TagMismatch {
+    pub expected: u16,
+    pub got: u16,
+}
+
+/// Command response.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Response<'a>(&'a [u8]);
+
+impl<'a> Response<'a> {
+    pub const HEADER_LEN: usize = 4;
+
+    /// Creates a new [`Response`].
+    ///
+    /// `buf` must be at least 4 bytes larger than `data` to fit the header.
+    /// The response only occupies the front of an over-sized buffer.
+    ///
+    /// Panics if `buf` is too small, in release builds too: the response can't
+    /// be written without dropping data. See [`Response::try_new`] for a
+    /// non-panicking version.
+    pub fn new(buf: &'a mut [u8], tag: u16, status: Status, status_info: u8, data: &[u8]) -> Self {
+        match Self::try_new(buf, tag, status, status_info, data) {
+            Ok(response) => response,
+            Err(err) => panic!("invalid response: {:?}", err),
+        }
+    }
+
+    /// Try to create a new [`Response`].
+    ///
+    /// Returns an error if `buf` is not at least 4 bytes larger than `data`.
+    pub fn try_new(
+        buf: &'a mut [u8],
+        tag: u16,
+        status: Status,
+        status_info: u8,
+        data: &[u8],
+    ) -> Result<Self, ResponseError> {
+        // ensure header and data will fit in buffer
+        let needed = data.len() + Self::HEADER_LEN;
+        if buf.len() < needed {
+            return Err(ResponseError::BufferTooSmall {
+                needed,
+                got: buf.len(),
+            });
+        }
+
+        let buf = &mut buf[..needed];
+        le::write_u16(buf, 0, tag).unwrap();
+        buf[2] = status.into();
+        buf[3] = status_info;
+        buf[Self::HEADER_LEN..].copy_from_slice(data);
+
+        Ok(Self(buf))
+    }
+
+    /// Creates a new [`Response`] to `request`, echoing its tag.
+    ///
+    /// See [`Response::new`].
+    pub fn reply_to(
+        buf: &'a mut [u8],
+        request: &Request,
+        status: Status,
+        status_info: u8,
+        data: &[u8],
+    ) -> Self {
+        Self::new(buf, request.tag(), status, status_info, data)
+    }
+
+    /// Creates a new [`Response`] from the result of a command handler.
+    ///
+    /// `Ok(data)` becomes a [`Status::Success`] response carrying `data`,
+    /// `Err((status, status_info))` becomes a response with that status and no
+    /// data.
+    ///
+    /// Panics if `buf` is too small to fit the header and data.
+    pub fn from_result(buf: &'a mut [u8], tag: u16, result: Result<&[u8], (Status, u8)>) -> Self {
+        let (status, status_info, data) = match result {
+            Ok(data) => (Status::Success, 0, data),
+            Err((status, status_info)) => (status, status_info, &[][..]),
+        };
+
+        Self::new(buf, tag, status, status_info, data)
+    }
+
+    /// Creates a new [`Response`] from a byte array.
+    ///
+    /// Panics if `buf` is shorter than the 4 byte header. See
+    /// [`Response::try_from_bytes`] for a non-panicking version.
+    pub fn from_bytes(buf: &'a [u8]) -> Self {
+        assert!(buf.len() >= Self::HEADER_LEN);
+        Self(buf)
+    }
+
+    /// Try to create a new [`Response`] from a byte array.
+    ///
+    /// Returns an error if `buf` is shorter than the 4 byte header.
+    pub fn try_from_bytes(buf: &'a [u8]) -> Result<Self, ResponseError> {
+        if buf.len() < Self::HEADER_LEN {
+            return Err(ResponseError::Truncated { len: buf.len() });
+        }
+
+        Ok(Self(buf))
+    }
+
+    /// Returns the tag.
+    pub fn tag(&self) -> u16 {
+        le::read_u16(self.0, 0).unwrap()
+    }
+
+    /// Returns the response if its tag is `tag`, otherwise a
+    /// [`TagMismatch`] error.
+    pub fn expect_tag(&self, tag: u16) -> Result<&Self, TagMismatch> {
+        match self.tag() {
+            got if got == tag => Ok(self),
+            got => Err(TagMismatch { expected: tag, got }),
+        }
+    }
+
+    /// Returns the status.
+    pub fn status(&self) -> Status {
+        Status::from(self.0[2])
+    }
+
+    /// Returns `true` if the response status is [`Status::Success`].
+    pub fn is_success(&self) -> bool {
+        self.status().is_success()
+    }
+
+    /// Returns `true` if the response status is an error.
+    ///
+    /// See [`Status::is_error`].
+    pub fn is_error(&self) -> bool {
+        self.status().is_error()
+    }
+
+    /// Returns the status info byte.
+    pub fn status_info(&self) -> StatusInfo {
+        StatusInfo(self.0[3])
+    }
+
+    /// Returns a slice containing the data.
+    pub fn data(&self) -> &[u8] {
+        &self.0[Self::HEADER_LEN..]
+    }
+
+    /// Split the serialized response into packets for sending.
+    ///
+    /// See [`Packet::fragments`].
+    pub fn fragments(&self) -> PacketFragments<'_> {
+        Packet::fragments(self.0)
+    }
+
+    /// Returns the data if the status is [`Status::Success`], otherwise a
+    /// [`StatusError`] carrying the status info byte.
+    ///
+    /// This is strict: [`Status::Other`] codes are returned as
+    /// [`StatusError::Other`] even if the device uses them for success. Use
+    /// [`Response::is_error`] to only reject the errors defined by the spec.
+    pub fn result(&self) -> Result<&[u8], StatusError> {
+        let status_info = self.status_info().get();
+
+        match self.status() {
+            Status::Success => Ok(self.data()),
+            Status::Unknown => Err(StatusError::Unknown { status_info }),
+            Status::Error => Err(StatusError::Error { status_info }),
+            Status::Other(status) => Err(StatusError::Other {
+                status,
+                status_info,
+            }),
+        }
+    }
+}
+
+/// In-place [`Response`] builder.
+///
+/// Writes the header up front and lets the payload be written directly into
+/// the buffer, avoiding a second copy.
+///
+/// The payload region is borrowed through [`ResponseBuilder::payload_mut`]
+/// rather than returned alongside the builder from `new`: the builder owns
+/// the whole buffer so it can hand it to [`ResponseBuilder::finish`], which a
+/// separately returned `&mut [u8]` over the same bytes would still be
+/// borrowing.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{ResponseBuilder, Status};
+/// let mut buf = [0; 64];
+/// let mut builder = ResponseBuilder::new(&mut buf, 1, Status::Success, 0);
+/// builder.payload_mut()[..3].copy_from_slice(&[1, 2, 3]);
+/// let response = builder.finish(3);
+/// assert_eq!(response.data(), &[1, 2, 3]);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ResponseBuilder<'a> {
+    buf: &'a mut [u8],
+}
+
+impl<'a> ResponseBuilder<'a> {
+    /// Creates a new [`ResponseBuilder`], writing the header into `buf`.
+    ///
+    /// Panics if `buf` is smaller than the 4 byte header.
+    pub fn new(buf: &'a mut [u8], tag: u16, status: Status, status_info: u8) -> Self {
+        assert!(buf.len() >= Response::HEADER_LEN);
+
+        le::write_u16(buf, 0, tag).unwrap();
+        buf[2] = status.into();
+        buf[3] = status_info;
+
+        Self { buf }
+    }
+
+    /// Returns the payload region following the header.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[Response::HEADER_LEN..]
+    }
+
+    /// Finalize into a [`Response`] with `payload_len` bytes of data.
+    ///
+    /// Panics if `payload_len` is larger than the payload region. See
+    /// [`ResponseBuilder::try_finish`] for a non-panicking version.
+    pub fn finish(self, payload_len: usize) -> Response<'a> {
+        match self.try_finish(payload_len) {
+            Ok(response) => response,
+            Err(err) => panic!("invalid response: {:?}", err),
+        }
+    }
+
+    /// Try to finalize into a [`Response`] with `payload_len` bytes of data.
+    ///
+    /// Returns an error if `payload_len` is larger than the payload region.
+    pub fn try_finish(self, payload_len: usize) -> Result<Response<'a>, ResponseError> {
+        let needed = Response::HEADER_LEN + payload_len;
+        if needed > self.buf.len() {
+            return Err(ResponseError::BufferTooSmall {
+                needed,
+                got: self.buf.len(),
+            });
+        }
+
+        Ok(Response(&self.buf[..needed]))
+    }
+}
+
+/// Split a raw transfer into a leading [`Response`] and the packets that
+/// follow it.
+///
+/// Assumes packed framing: the transfer starts with a single
+/// [`PacketKind::CommandFinal`] packet carrying the whole response, and the
+/// next packet starts immediately after the length declared in its header.
+/// The remainder is typically [`PacketKind::StdOut`] or [`PacketKind::StdErr`]
+/// packets.
+///
+/// Returns `None` if the leading packet is truncated, isn't a
+/// [`PacketKind::CommandFinal`] packet or is shorter than the response header.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::split_response_and_output;
+/// let buf = [0x44, 0x01, 0x00, 0x00, 0x00, 0x82, b'h', b'i'];
+/// let (response, mut output) = split_response_and_output(&buf).unwrap();
+/// assert_eq!(response.tag(), 1);
+/// assert_eq!(output.next().unwrap().data(), b"hi");
+/// ```
+pub fn split_response_and_output(buf: &[u8]) -> Option<(Response<'_>, PacketIter<'_>)> {
+    let packet = Packet::try_from_bytes(buf).ok()?;
+
+    if packet.kind() != PacketKind::CommandFinal {
+        return None;
+    }
+
+    let (packet, rest) = buf.split_at(packet.len());
+    let response = Response::try_from_bytes(&packet[1..]).ok()?;
+    let rest = PacketIter::new(rest, Stride::Packed);
+
+    Some((response, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command() {
         let command = Command::from(u32::MAX);
         assert_eq!(command, Command::Other(u32::MAX));
 
@@ -258,6 +1092,804 @@ mod tests {
         assert_eq!(value, output);
     }
 
+    #[test]
+    fn test_command_from() {
+        assert_eq!(u32::from(Command::WriteFlashPage), 0x0006);
+        assert_eq!(u32::from(Command::Other(0x5A5A)), 0x5A5A);
+        assert_eq!(u8::from(Status::Error), 0x02);
+        assert_eq!(u8::from(Status::Other(0x80)), 0x80);
+    }
+
+    #[test]
+    fn test_command_try_from_standard() {
+        assert_eq!(
+            Command::try_from_standard(0x0006),
+            Ok(Command::WriteFlashPage)
+        );
+        assert_eq!(Command::try_from_standard(0x0010), Ok(Command::Dmesg));
+        assert_eq!(
+            Command::try_from_standard(0xFFFF_FFFF),
+            Err(UnknownCommand(0xFFFF_FFFF))
+        );
+    }
+
+    #[test]
+    fn test_request_new_parts() {
+        let addr = 0x0800_1000u32.to_le_bytes();
+        let page: [u8; 32] = core::array::from_fn(|i| i as u8);
+
+        let mut buf = [0xFF; 64];
+        let request = Request::new_parts(&mut buf, Command::WriteFlashPage, 4, &[&addr, &page]);
+        assert_eq!(request.command(), Command::WriteFlashPage);
+        assert_eq!(request.tag(), 4);
+        assert_eq!(request.reserved(), [0; 2]);
+        assert_eq!(request.len(), 36);
+        assert_eq!(
+            flash::parse_write_flash_page(&request),
+            Ok((0x0800_1000, &page[..]))
+        );
+
+        let mut buf = [0; 8];
+        let request = Request::new_parts(&mut buf, Command::Info, 1, &[]);
+        assert!(request.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_request_new_parts_too_large() {
+        let mut buf = [0; 12];
+        Request::new_parts(&mut buf, Command::WriteWords, 0, &[&[0; 4], &[0; 1]]);
+    }
+
+    #[test]
+    fn test_request_writer() {
+        let page: [u8; 16] = core::array::from_fn(|i| i as u8);
+
+        let mut buf = [0xFF; 64];
+        let mut writer = RequestWriter::new(&mut buf, Command::WriteFlashPage, 0x55).unwrap();
+        assert_eq!(writer.capacity(), 56);
+
+        let body = writer.body_mut();
+        le::write_u32(body, 0, 0x0800_0000).unwrap();
+        body[4..][..page.len()].copy_from_slice(&page);
+
+        let request = writer.finish(4 + page.len()).unwrap();
+        assert_eq!(request.command(), Command::WriteFlashPage);
+        assert_eq!(request.tag(), 0x55);
+        assert_eq!(request.reserved(), [0; 2]);
+        assert_eq!(request.total_len(), 28);
+        assert_eq!(&request.data()[..4], &0x0800_0000u32.to_le_bytes());
+        assert_eq!(&request.data()[4..], &page);
+
+        let mut expected = [0; 64];
+        let expected = Request::new(&mut expected, Command::WriteFlashPage, 0x55, request.data());
+        assert!(request.eq_bytes(&expected));
+    }
+
+    #[test]
+    fn test_request_writer_errors() {
+        let mut buf = [0; 7];
+        assert_eq!(
+            RequestWriter::new(&mut buf, Command::Info, 0).unwrap_err(),
+            RequestError::BufferTooSmall { needed: 8, got: 7 }
+        );
+
+        let mut buf = [0; 12];
+        let writer = RequestWriter::new(&mut buf, Command::Info, 0).unwrap();
+        assert_eq!(
+            writer.finish(5).unwrap_err(),
+            RequestError::BufferTooSmall {
+                needed: 13,
+                got: 12
+            }
+        );
+
+        let mut buf = [0; 8];
+        let writer = RequestWriter::new(&mut buf, Command::Info, 0).unwrap();
+        assert!(writer.finish(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_command_category() {
+        let categories = [
+            (Command::BinInfo, Category::Info),
+            (Command::Info, Category::Info),
+            (Command::ResetIntoApp, Category::Control),
+            (Command::ResetIntoBootloader, Category::Control),
+            (Command::StartFlash, Category::Flash),
+            (Command::WriteFlashPage, Category::Flash),
+            (Command::ChecksumPages, Category::Flash),
+            (Command::ReadWords, Category::Memory),
+            (Command::WriteWords, Category::Memory),
+            (Command::Dmesg, Category::Info),
+            (Command::Other(0x8000), Category::Other),
+        ];
+
+        for (command, category) in categories {
+            assert_eq!(command.category(), category);
+        }
+    }
+
+    #[test]
+    fn test_command_all() {
+        assert_eq!(Command::ALL.len(), 10);
+
+        for command in Command::ALL {
+            assert!(command.is_standard());
+            assert_eq!(
+                Command::try_from_standard(u32::from(*command)),
+                Ok(*command)
+            );
+        }
+    }
+
+    #[test]
+    fn test_command_round_trip() {
+        let commands = [
+            (0x0001, Command::BinInfo),
+            (0x0002, Command::Info),
+            (0x0003, Command::ResetIntoApp),
+            (0x0004, Command::ResetIntoBootloader),
+            (0x0005, Command::StartFlash),
+            (0x0006, Command::WriteFlashPage),
+            (0x0007, Command::ChecksumPages),
+            (0x0008, Command::ReadWords),
+            (0x0009, Command::WriteWords),
+            (0x0010, Command::Dmesg),
+        ];
+
+        for (id, command) in commands {
+            assert_eq!(Command::from(id), command);
+            let output: u32 = command.into();
+            assert_eq!(output, id);
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_status_success() {
+        assert_eq!(Status::Sucess, Status::Success);
+        assert_eq!(Status::from(0x00), Status::Success);
+
+        let success: u8 = Status::Success.into();
+        let sucess: u8 = Status::Sucess.into();
+        assert_eq!(success, 0x00);
+        assert_eq!(sucess, 0x00);
+    }
+
+    #[test]
+    fn test_is_success() {
+        assert!(Status::Success.is_success());
+        assert!(!Status::Unknown.is_success());
+        assert!(!Status::Error.is_success());
+        assert!(!Status::Other(0x7F).is_success());
+
+        for (status, success) in [(0x00, true), (0x01, false), (0x02, false), (0x7F, false)] {
+            let buf = [0x01, 0x00, status, 0x00];
+            let response = Response::from_bytes(&buf);
+            assert_eq!(response.is_success(), success);
+        }
+    }
+
+    #[test]
+    fn test_response_expect_tag() {
+        let buf = [0x34, 0x12, 0x00, 0x00, 0xAA];
+        let response = Response::from_bytes(&buf);
+        assert_eq!(response.expect_tag(0x1234).unwrap().data(), &[0xAA]);
+        assert_eq!(
+            response.expect_tag(0x1235).unwrap_err(),
+            TagMismatch {
+                expected: 0x1235,
+                got: 0x1234
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_error() {
+        assert!(!Status::Success.is_error());
+        assert!(Status::Unknown.is_error());
+        assert!(Status::Error.is_error());
+
+        // device-defined, neither success nor error
+        let accepted = Status::Other(0x10);
+        assert!(!accepted.is_error());
+        assert!(!accepted.is_success());
+
+        let buf = [0x01, 0x00, 0x10, 0x00];
+        let response = Response::from_bytes(&buf);
+        assert!(!response.is_error());
+        assert!(!response.is_success());
+
+        let buf = [0x01, 0x00, 0x02, 0x00];
+        assert!(Response::from_bytes(&buf).is_error());
+    }
+
+    #[test]
+    fn test_status_is_reserved() {
+        assert!(Status::from(0x03).is_reserved());
+        assert!(Status::from(0x0F).is_reserved());
+
+        assert!(!Status::from(0x10).is_reserved());
+        assert!(!Status::from(0x80).is_reserved());
+        assert!(!Status::Success.is_reserved());
+        assert!(!Status::Unknown.is_reserved());
+        assert!(!Status::Error.is_reserved());
+    }
+
+    #[test]
+    fn test_response_try_new() {
+        let mut buf = [0; 7];
+        let response = Response::try_new(&mut buf, 9, Status::Success, 0, &[1, 2, 3]).unwrap();
+        assert_eq!(response.tag(), 9);
+        assert_eq!(response.data(), &[1, 2, 3]);
+
+        let mut buf = [0; 6];
+        assert_eq!(
+            Response::try_new(&mut buf, 9, Status::Success, 0, &[1, 2, 3]).unwrap_err(),
+            ResponseError::BufferTooSmall { needed: 7, got: 6 }
+        );
+    }
+
+    #[test]
+    fn test_response_oversized_buffer() {
+        let mut buf = [0xFF; 64];
+        let response = Response::new(&mut buf, 2, Status::Success, 0, &[1, 2, 3, 4, 5]);
+        assert_eq!(response.data().len(), 5);
+        assert_eq!(response.data(), &[1, 2, 3, 4, 5]);
+        assert_eq!(buf[9], 0xFF);
+    }
+
+    #[test]
+    fn test_response_fragments() {
+        let words: [u8; 100] = core::array::from_fn(|i| i as u8);
+
+        let mut buf = [0; 104];
+        let response = Response::new(&mut buf, 0x0102, Status::Success, 0, &words);
+        let mut fragments = response.fragments();
+
+        let mut storage = [0; 128];
+        let mut reassembler = crate::reassembly::Reassembler::new(&mut storage);
+        let mut packet_buf = [0; 64];
+
+        let packet = fragments.next(&mut packet_buf).unwrap();
+        assert_eq!(packet.kind(), PacketKind::CommandInner);
+        assert_eq!(packet.payload_len(), Packet::MAX_LEN);
+        assert_eq!(reassembler.push(&packet), Ok(None));
+
+        let packet = fragments.next(&mut packet_buf).unwrap();
+        assert_eq!(packet.kind(), PacketKind::CommandFinal);
+        assert_eq!(packet.payload_len(), 104 - Packet::MAX_LEN);
+
+        let message = reassembler.push(&packet).unwrap().unwrap();
+        let received = Response::from_bytes(message);
+        assert_eq!(received.tag(), 0x0102);
+        assert_eq!(received.result(), Ok(&words[..]));
+
+        assert!(fragments.next(&mut packet_buf).is_none());
+    }
+
+    #[test]
+    fn test_response_fragments_single() {
+        let mut buf = [0; 8];
+        let response = Response::new(&mut buf, 7, Status::Error, 1, &[]);
+        let mut fragments = response.fragments();
+
+        let mut packet_buf = [0; 64];
+        let packet = fragments.next(&mut packet_buf).unwrap();
+        assert_eq!(packet.kind(), PacketKind::CommandFinal);
+        assert_eq!(packet.data(), &[0x07, 0x00, 0x02, 0x01]);
+        assert!(fragments.next(&mut packet_buf).is_none());
+    }
+
+    #[test]
+    fn test_response_reply_to() {
+        let mut buf = [0; 8];
+        let request = Request::new(&mut buf, Command::Info, 0xBEEF, &[]);
+
+        let mut buf = [0; 64];
+        let response = Response::reply_to(&mut buf, &request, Status::Success, 0, b"ok");
+        assert_eq!(response.tag(), request.tag());
+        assert_eq!(response.tag(), 0xBEEF);
+        assert_eq!(response.data(), b"ok");
+    }
+
+    #[test]
+    fn test_response_from_result() {
+        let mut buf = [0xFF; 64];
+        let response = Response::from_result(&mut buf, 3, Ok(&[1, 2, 3]));
+        assert_eq!(response.tag(), 3);
+        assert_eq!(response.status(), Status::Success);
+        assert_eq!(response.status_info(), 0);
+        assert_eq!(response.data(), &[1, 2, 3]);
+
+        let mut buf = [0xFF; 64];
+        let response = Response::from_result(&mut buf, 4, Err((Status::Error, 0x21)));
+        assert_eq!(response.tag(), 4);
+        assert_eq!(response.status(), Status::Error);
+        assert_eq!(response.status_info(), 0x21);
+        assert_eq!(response.data(), &[0u8; 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_response_from_result_too_small() {
+        let mut buf = [0; 6];
+        Response::from_result(&mut buf, 1, Ok(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_response_builder() {
+        let mut buf = [0xFF; 16];
+        let mut builder = ResponseBuilder::new(&mut buf, 0x1234, Status::Error, 0x05);
+        assert_eq!(builder.payload_mut().len(), 12);
+        builder.payload_mut()[..2].copy_from_slice(&[0xAA, 0xBB]);
+
+        // under-filled
+        let response = builder.finish(2);
+        assert_eq!(response.tag(), 0x1234);
+        assert_eq!(response.status(), Status::Error);
+        assert_eq!(response.status_info(), 0x05);
+        assert_eq!(response.data(), &[0xAA, 0xBB]);
+
+        // completely filled
+        let mut buf = [0; 8];
+        let mut builder = ResponseBuilder::new(&mut buf, 1, Status::Success, 0);
+        builder.payload_mut().fill(0x11);
+        let response = builder.finish(4);
+        assert_eq!(response.data(), &[0x11; 4]);
+
+        // over-filled
+        let mut buf = [0; 8];
+        let builder = ResponseBuilder::new(&mut buf, 1, Status::Success, 0);
+        assert_eq!(
+            builder.try_finish(5).unwrap_err(),
+            ResponseError::BufferTooSmall { needed: 9, got: 8 }
+        );
+    }
+
+    #[test]
+    fn test_response_builder_under_fill() {
+        let mut buf = [0xFF; 16];
+        let builder = ResponseBuilder::new(&mut buf, 2, Status::Success, 0);
+        let response = builder.finish(0);
+        assert!(response.data().is_empty());
+        assert_eq!(response.result(), Ok(&[][..]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_response_builder_over_fill() {
+        let mut buf = [0; 8];
+        let builder = ResponseBuilder::new(&mut buf, 1, Status::Success, 0);
+        builder.finish(5);
+    }
+
+    #[test]
+    fn test_response_try_from_bytes() {
+        let response = Response::try_from_bytes(&[0x01, 0x00, 0x00, 0x00, 0xAA]).unwrap();
+        assert_eq!(response.tag(), 1);
+        assert_eq!(response.data(), &[0xAA]);
+
+        assert_eq!(
+            Response::try_from_bytes(&[0x01, 0x00, 0x00]).unwrap_err(),
+            ResponseError::Truncated { len: 3 }
+        );
+    }
+
+    #[test]
+    fn test_split_response_and_output() {
+        let buf = [
+            0x46, 0x07, 0x00, 0x00, 0x00, 0xAA, 0xBB, // response
+            0x83, b'o', b'n', b'e', // stdout
+            0x83, b't', b'w', b'o', // stdout
+        ];
+
+        let (response, mut output) = split_response_and_output(&buf).unwrap();
+        assert_eq!(response.tag(), 7);
+        assert!(response.is_success());
+        assert_eq!(response.data(), &[0xAA, 0xBB]);
+
+        let packet = output.next().unwrap();
+        assert!(packet.kind() == PacketKind::StdOut);
+        assert_eq!(packet.data(), b"one");
+
+        let packet = output.next().unwrap();
+        assert!(packet.kind() == PacketKind::StdOut);
+        assert_eq!(packet.data(), b"two");
+
+        assert!(output.next().is_none());
+    }
+
+    #[test]
+    fn test_split_response_and_output_invalid() {
+        // not a final packet
+        assert!(split_response_and_output(&[0x84, 0x07, 0x00, 0x00, 0x00]).is_none());
+        // shorter than the response header
+        assert!(split_response_and_output(&[0x42, 0x07, 0x00]).is_none());
+        // truncated
+        assert!(split_response_and_output(&[0x48, 0x07, 0x00, 0x00, 0x00]).is_none());
+        assert!(split_response_and_output(&[]).is_none());
+    }
+
+    #[test]
+    fn test_status_info() {
+        let mut buf = [0; 4];
+        let response = Response::new(&mut buf, 1, Status::Error, 22, &[]);
+
+        let info = response.status_info();
+        assert_eq!(info, StatusInfo(22));
+        assert_eq!(info, 22);
+        assert_eq!(*info, 22);
+        assert_eq!(info.get(), 22);
+        assert_eq!(u8::from(info), 22);
+        assert_eq!(StatusInfo::from(22), info);
+
+        let mut buf = [0; 4];
+        let response = Response::new(&mut buf, 1, Status::Success, 0, &[]);
+        assert_eq!(response.status_info(), StatusInfo::NONE);
+        assert!(matches!(response.status_info(), StatusInfo(0)));
+    }
+
+    #[test]
+    fn test_response_result() {
+        let mut buf = [0; 6];
+        let response = Response::new(&mut buf, 1, Status::Success, 0, &[0xAA, 0xBB]);
+        assert_eq!(response.result(), Ok(&[0xAA, 0xBB][..]));
+
+        let mut buf = [0; 4];
+        let response = Response::new(&mut buf, 1, Status::Unknown, 0x12, &[]);
+        assert_eq!(
+            response.result(),
+            Err(StatusError::Unknown { status_info: 0x12 })
+        );
+
+        let mut buf = [0; 4];
+        let response = Response::new(&mut buf, 1, Status::Error, 0x34, &[]);
+        assert_eq!(
+            response.result(),
+            Err(StatusError::Error { status_info: 0x34 })
+        );
+
+        let mut buf = [0; 4];
+        let response = Response::new(&mut buf, 1, Status::Other(0x80), 0x56, &[]);
+        assert_eq!(
+            response.result(),
+            Err(StatusError::Other {
+                status: 0x80,
+                status_info: 0x56
+            })
+        );
+    }
+
+    #[test]
+    fn test_command_name() {
+        let names = [
+            (Command::BinInfo, "BinInfo"),
+            (Command::Info, "Info"),
+            (Command::ResetIntoApp, "ResetIntoApp"),
+            (Command::ResetIntoBootloader, "ResetIntoBootloader"),
+            (Command::StartFlash, "StartFlash"),
+            (Command::WriteFlashPage, "WriteFlashPage"),
+            (Command::ChecksumPages, "ChecksumPages"),
+            (Command::ReadWords, "ReadWords"),
+            (Command::WriteWords, "WriteWords"),
+            (Command::Dmesg, "Dmesg"),
+            (Command::Other(0x1234), "Other"),
+        ];
+
+        for (command, name) in names {
+            assert_eq!(command.name(), name);
+        }
+    }
+
+    #[test]
+    fn test_command_display() {
+        assert_eq!(Command::BinInfo.to_string(), "BinInfo");
+        assert_eq!(Command::WriteFlashPage.to_string(), "WriteFlashPage");
+        assert_eq!(Command::Dmesg.to_string(), "Dmesg");
+        assert_eq!(Command::Other(0x5A5A).to_string(), "Other(0x5A5A)");
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let mut buf = [0; 8];
+        let reset = Request::reset_into_app(&mut buf, 1);
+        assert_eq!(validate_request(&reset), Ok(()));
+
+        let mut buf = [0; 10];
+        let reset = Request::new(&mut buf, Command::ResetIntoApp, 1, &[0, 0]);
+        assert_eq!(
+            validate_request(&reset),
+            Err(CommandError::DataTooLong { max: 0, got: 2 })
+        );
+
+        let mut buf = [0; 16];
+        let read = ReadWordsRequest::new(&mut buf, 1, 0x2000_0000, 4);
+        assert_eq!(validate_request(read.request()), Ok(()));
+
+        let mut buf = [0; 12];
+        let read = Request::new(&mut buf, Command::ReadWords, 1, &[0; 4]);
+        assert_eq!(
+            validate_request(&read),
+            Err(CommandError::DataTooShort { needed: 8, got: 4 })
+        );
+
+        let mut buf = [0; 20];
+        let other = Request::new(&mut buf, Command::Other(0x8000), 1, &[0; 12]);
+        assert_eq!(validate_request(&other), Ok(()));
+    }
+
+    #[test]
+    fn test_expected_data_len() {
+        assert_eq!(
+            Command::ResetIntoApp.expected_data_len(),
+            ExpectedLen::Exact(0)
+        );
+        assert_eq!(
+            Command::WriteFlashPage.expected_data_len(),
+            ExpectedLen::Min(4)
+        );
+        assert_eq!(
+            Command::ReadWords.expected_data_len(),
+            ExpectedLen::Exact(8)
+        );
+        assert_eq!(
+            Command::Other(0x8000).expected_data_len(),
+            ExpectedLen::Variable
+        );
+    }
+
+    #[test]
+    fn test_validate_request_variable() {
+        for len in [0, 1, 100] {
+            let mut buf = [0; 108];
+            let request = Request::new(&mut buf, Command::Other(0x8000), 0, &[0; 100][..len]);
+            assert_eq!(validate_request(&request), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_command_to_le_bytes() {
+        const BYTES: [u8; 4] = Command::WriteFlashPage.to_le_bytes();
+        assert_eq!(BYTES, [0x06, 0, 0, 0]);
+        assert_eq!(Command::Dmesg.to_le_bytes(), [0x10, 0, 0, 0]);
+        assert_eq!(
+            Command::Other(0x1234_5678).to_le_bytes(),
+            [0x78, 0x56, 0x34, 0x12]
+        );
+    }
+
+    #[test]
+    fn test_command_debug() {
+        assert_eq!(format!("{:?}", Command::BinInfo), "BinInfo");
+        assert_eq!(format!("{:?}", Command::Other(0x5A5A)), "Other(0x5A5A)");
+        assert_eq!(
+            format!("{:?}", Some(Command::Other(0x10000))),
+            "Some(Other(0x10000))"
+        );
+    }
+
+    #[test]
+    fn test_status_display() {
+        assert_eq!(Status::Success.to_string(), "Success");
+        assert_eq!(Status::Unknown.to_string(), "Unknown");
+        assert_eq!(Status::Error.to_string(), "Error");
+        assert_eq!(Status::Other(0x80).to_string(), "Other(128)");
+    }
+
+    #[test]
+    fn test_command_is_standard() {
+        assert!(Command::WriteFlashPage.is_standard());
+        assert!(Command::from(0x0010).is_standard());
+        assert!(!Command::Other(0x8000).is_standard());
+        assert!(!Command::from(0x0011).is_standard());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_command_serde() {
+        let json = serde_json::to_string(&Command::WriteFlashPage).unwrap();
+        assert_eq!(json, r#""WriteFlashPage""#);
+        assert_eq!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::WriteFlashPage
+        );
+
+        let json = serde_json::to_string(&Command::Other(0x5A5A)).unwrap();
+        assert_eq!(json, r#"{"Other":23130}"#);
+        assert_eq!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::Other(0x5A5A)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_status_serde() {
+        let json = serde_json::to_string(&Status::Success).unwrap();
+        assert_eq!(json, r#""Success""#);
+        assert_eq!(
+            serde_json::from_str::<Status>(&json).unwrap(),
+            Status::Success
+        );
+
+        let json = serde_json::to_string(&Status::Other(0x80)).unwrap();
+        assert_eq!(json, r#"{"Other":128}"#);
+        assert_eq!(
+            serde_json::from_str::<Status>(&json).unwrap(),
+            Status::Other(0x80)
+        );
+    }
+
+    #[test]
+    fn test_request_reset() {
+        let mut buf = [0xFF; Request::HEADER_LEN];
+        let request = Request::reset_into_app(&mut buf, 5);
+        assert_eq!(request.command(), Command::ResetIntoApp);
+        assert_eq!(request.tag(), 5);
+        assert_eq!(request.len(), 0);
+
+        let mut buf = [0xFF; Request::HEADER_LEN];
+        let request = Request::reset_into_bootloader(&mut buf, 6);
+        assert_eq!(request.command(), Command::ResetIntoBootloader);
+        assert_eq!(request.tag(), 6);
+        assert_eq!(request.len(), 0);
+    }
+
+    #[test]
+    fn test_request_total_len() {
+        let mut buf = [0xFF; Request::HEADER_LEN];
+        let request = Request::reset_into_app(&mut buf, 5);
+        assert!(request.is_empty());
+        assert_eq!(request.total_len(), Request::HEADER_LEN);
+
+        let mut buf = [0xFF; 64];
+        let write = flash::WriteFlashPage::new(&mut buf, 1, 0x2000_0000, &[0xAA; 16]);
+        let request = write.request();
+        assert!(!request.is_empty());
+        assert_eq!(request.len(), 20);
+        assert_eq!(request.total_len(), Request::HEADER_LEN + 20);
+    }
+
+    #[test]
+    fn test_request_start_flash() {
+        let mut buf = [0xFF; Request::HEADER_LEN];
+        let request = Request::start_flash(&mut buf, 7);
+        assert_eq!(request.command(), Command::StartFlash);
+        assert_eq!(request.tag(), 7);
+        assert_eq!(request.len(), 0);
+    }
+
+    #[test]
+    fn test_request_builder() {
+        let mut buf = [0xFF; 64];
+        let request = Request::builder()
+            .command(Command::WriteFlashPage)
+            .tag(0x1234)
+            .reserved([0x01, 0x02])
+            .data(&[0xAA; 8])
+            .build_into(&mut buf)
+            .unwrap();
+
+        assert_eq!(request.command(), Command::WriteFlashPage);
+        assert_eq!(request.tag(), 0x1234);
+        assert_eq!(request.reserved(), [0x01, 0x02]);
+        assert_eq!(request.data(), &[0xAA; 8]);
+        assert_eq!(request.len(), 8);
+    }
+
+    #[test]
+    fn test_request_builder_errors() {
+        let mut buf = [0; 12];
+        assert_eq!(
+            Request::builder()
+                .command(Command::Info)
+                .data(&[0; 8])
+                .build_into(&mut buf)
+                .unwrap_err(),
+            RequestError::BufferTooSmall {
+                needed: 16,
+                got: 12
+            }
+        );
+
+        assert_eq!(
+            Request::builder().tag(1).build_into(&mut buf).unwrap_err(),
+            RequestError::MissingCommand
+        );
+    }
+
+    #[test]
+    fn test_request_reserved() {
+        let mut buf = [0; 10];
+        let request = Request::new(&mut buf, Command::Info, 1, &[0xAA, 0xBB]);
+        assert_eq!(request.reserved(), [0, 0]);
+
+        let mut buf = [0; 10];
+        let request =
+            Request::new_with_reserved(&mut buf, Command::Info, 1, [0x12, 0x34], &[0xAA, 0xBB]);
+        assert_eq!(request.reserved(), [0x12, 0x34]);
+        assert_eq!(request.tag(), 1);
+        assert_eq!(request.data(), &[0xAA, 0xBB]);
+    }
+
+    #[test]
+    fn test_request_oversized_buffer() {
+        let mut buf = [0xFF; 64];
+        let request = Request::new(&mut buf, Command::Info, 1, &[0xAA, 0xBB]);
+        assert_eq!(request.len(), 2);
+        assert_eq!(request.total_len(), 10);
+        assert_eq!(request.data(), &[0xAA, 0xBB]);
+
+        let mut packets = request.into_packet_iter();
+        let mut packet_buf = [0; 64];
+        let packet = packets.next(&mut packet_buf).unwrap();
+        assert_eq!(packet.payload_len(), 10);
+
+        let mut buf = [0xFF; 64];
+        let request = Request::reset_into_app(&mut buf, 2);
+        assert!(request.is_empty());
+        assert_eq!(buf[8], 0xFF);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_request_buffer_too_small() {
+        let mut buf = [0; 9];
+        Request::new(&mut buf, Command::Info, 1, &[0xAA, 0xBB]);
+    }
+
+    #[test]
+    fn test_request_command_id() {
+        let mut buf = [0; 8];
+        let request = Request::new(&mut buf, Command::Other(0xDEAD_BEEF), 1, &[]);
+        assert_eq!(request.command_id(), 0xDEAD_BEEF);
+        assert_eq!(request.command(), Command::Other(0xDEAD_BEEF));
+
+        let request = Request::from_bytes(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(request.command_id(), 0x0001);
+    }
+
+    #[test]
+    fn test_request_eq() {
+        let mut buf = [0; 10];
+        let request = Request::new(&mut buf, Command::Info, 1, &[0xAA, 0xBB]);
+
+        let mut other_buf = [0; 10];
+        let other = Request::new_with_reserved(
+            &mut other_buf,
+            Command::Info,
+            1,
+            [0x12, 0x34],
+            &[0xAA, 0xBB],
+        );
+
+        assert_eq!(request, other);
+        assert!(!request.eq_bytes(&other));
+        assert!(request.eq_bytes(&request));
+
+        let mut buf = [0; 10];
+        let other_tag = Request::new(&mut buf, Command::Info, 2, &[0xAA, 0xBB]);
+        assert_ne!(request, other_tag);
+
+        let mut buf = [0; 10];
+        let other_command = Request::new(&mut buf, Command::Dmesg, 1, &[0xAA, 0xBB]);
+        assert_ne!(request, other_command);
+
+        let mut buf = [0; 10];
+        let other_data = Request::new(&mut buf, Command::Info, 1, &[0xAA, 0xBC]);
+        assert_ne!(request, other_data);
+    }
+
+    #[test]
+    fn test_request_reserved_cleared() {
+        let mut buf = [0xFF; 12];
+        let request = Request::new(&mut buf, Command::WriteWords, 0xABCD, &[0x11; 4]);
+        assert_eq!(request.reserved(), [0, 0]);
+        assert_eq!(&buf[6..8], &[0x00, 0x00]);
+    }
+
     #[test]
     fn test_request() {
         let mut buf = [0; 256];
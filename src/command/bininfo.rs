@@ -0,0 +1,216 @@
+use crate::le;
+
+/// Bin info mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[repr(u32)]
+pub enum Mode {
+    /// Running the bootloader.
+    Bootloader = 0x0001,
+    /// Running the user-space application.
+    Application = 0x0002,
+    /// Any other mode.
+    Other(u32),
+}
+
+impl From<u32> for Mode {
+    fn from(value: u32) -> Self {
+        match value {
+            0x0001 => Self::Bootloader,
+            0x0002 => Self::Application,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+impl From<Mode> for u32 {
+    fn from(value: Mode) -> Self {
+        match value {
+            Mode::Bootloader => 0x0001,
+            Mode::Application => 0x0002,
+            Mode::Other(value) => value,
+        }
+    }
+}
+
+/// UF2 chip family ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FamilyId(pub u32);
+
+impl FamilyId {
+    /// Microchip ATSAMD21.
+    pub const SAMD21: Self = Self(0x68ED2B88);
+    /// Microchip ATSAMD51.
+    pub const SAMD51: Self = Self(0x55114460);
+    /// Nordic nRF52840.
+    pub const NRF52840: Self = Self(0xADA52840);
+    /// ST STM32F4xx.
+    pub const STM32F4: Self = Self(0x57755A57);
+    /// Raspberry Pi RP2040.
+    pub const RP2040: Self = Self(0xE48BFF56);
+
+    /// Returns the raw family ID.
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+impl core::fmt::Display for FamilyId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:#010X}", self.0)
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for FamilyId {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u32:#010X}", self.0)
+    }
+}
+
+impl From<u32> for FamilyId {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<FamilyId> for u32 {
+    fn from(value: FamilyId) -> Self {
+        value.0
+    }
+}
+
+/// Bin info response.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::bininfo::{BinInfo, Mode};
+/// let data = [
+///     0x01, 0x00, 0x00, 0x00, // mode
+///     0x00, 0x01, 0x00, 0x00, // flash page size
+///     0x00, 0x04, 0x00, 0x00, // flash number of pages
+///     0x40, 0x00, 0x00, 0x00, // max message size
+/// ];
+/// let info = BinInfo::from_data(&data).unwrap();
+/// assert_eq!(info.mode(), Mode::Bootloader);
+/// assert_eq!(info.flash_page_size(), 256);
+/// assert_eq!(info.family_id(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct BinInfo {
+    mode: Mode,
+    flash_page_size: u32,
+    flash_num_pages: u32,
+    max_message_size: u32,
+    family_id: Option<FamilyId>,
+}
+
+impl BinInfo {
+    /// Length of the response data without the family ID.
+    pub const MIN_LEN: usize = 16;
+
+    /// Length of the response data with the family ID.
+    pub const LEN: usize = 20;
+
+    /// Parse from the response data.
+    ///
+    /// Returns `None` if `data` is shorter than [`BinInfo::MIN_LEN`].
+    pub fn from_data(data: &[u8]) -> Option<Self> {
+        if data.len() < Self::MIN_LEN {
+            return None;
+        }
+
+        Some(Self {
+            mode: Mode::from(le::read_u32(data, 0)?),
+            flash_page_size: le::read_u32(data, 4)?,
+            flash_num_pages: le::read_u32(data, 8)?,
+            max_message_size: le::read_u32(data, 12)?,
+            family_id: le::read_u32(data, 16).map(FamilyId),
+        })
+    }
+
+    /// Returns the mode.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the flash page size in bytes.
+    pub fn flash_page_size(&self) -> u32 {
+        self.flash_page_size
+    }
+
+    /// Returns the number of flash pages.
+    pub fn flash_num_pages(&self) -> u32 {
+        self.flash_num_pages
+    }
+
+    /// Returns the maximum message size in bytes.
+    pub fn max_message_size(&self) -> u32 {
+        self.max_message_size
+    }
+
+    /// Returns the family ID, if present.
+    pub fn family_id(&self) -> Option<FamilyId> {
+        self.family_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: [u8; 20] = [
+        0x02, 0x00, 0x00, 0x00, // mode
+        0x00, 0x02, 0x00, 0x00, // flash page size
+        0x00, 0x01, 0x00, 0x00, // flash number of pages
+        0x00, 0x10, 0x00, 0x00, // max message size
+        0x40, 0x28, 0xA5, 0xAD, // family id
+    ];
+
+    #[test]
+    fn test_mode() {
+        assert_eq!(Mode::from(1), Mode::Bootloader);
+        assert_eq!(Mode::from(2), Mode::Application);
+        assert_eq!(Mode::from(0), Mode::Other(0));
+        assert_eq!(Mode::from(u32::MAX), Mode::Other(u32::MAX));
+
+        for value in [0, 1, 2, 3, u32::MAX] {
+            let output: u32 = Mode::from(value).into();
+            assert_eq!(value, output);
+        }
+    }
+
+    #[test]
+    fn test_bininfo() {
+        let info = BinInfo::from_data(&DATA).unwrap();
+        assert_eq!(info.mode(), Mode::Application);
+        assert_eq!(info.flash_page_size(), 512);
+        assert_eq!(info.flash_num_pages(), 256);
+        assert_eq!(info.max_message_size(), 4096);
+        assert_eq!(info.family_id(), Some(FamilyId::NRF52840));
+    }
+
+    #[test]
+    fn test_family_id() {
+        assert_eq!(FamilyId::from(0xADA52840), FamilyId::NRF52840);
+        assert_eq!(u32::from(FamilyId::SAMD51), 0x55114460);
+        assert_eq!(FamilyId::RP2040.get(), 0xE48BFF56);
+        assert_eq!(FamilyId::NRF52840.to_string(), "0xADA52840");
+        assert_eq!(FamilyId(0x1).to_string(), "0x00000001");
+    }
+
+    #[test]
+    fn test_bininfo_without_family_id() {
+        let info = BinInfo::from_data(&DATA[..16]).unwrap();
+        assert_eq!(info.max_message_size(), 4096);
+        assert_eq!(info.family_id(), None);
+    }
+
+    #[test]
+    fn test_bininfo_too_short() {
+        assert_eq!(BinInfo::from_data(&DATA[..15]), None);
+        assert_eq!(BinInfo::from_data(&[]), None);
+    }
+}
@@ -0,0 +1,207 @@
+use super::Response;
+
+/// Iterate over the raw lines of a [`Command::Dmesg`](super::Command::Dmesg)
+/// response.
+///
+/// Lines are split on `\n` with any trailing `\r` removed. A trailing empty
+/// line is skipped.
+pub fn raw_lines<'a>(response: &'a Response) -> impl Iterator<Item = &'a [u8]> {
+    let data = response.data();
+    let data = data.strip_suffix(b"\n").unwrap_or(data);
+
+    (!data.is_empty())
+        .then_some(data)
+        .into_iter()
+        .flat_map(|data| data.split(|&b| b == b'\n'))
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+/// Iterate over the lines of a [`Command::Dmesg`](super::Command::Dmesg)
+/// response.
+///
+/// Lines are split the same way as [`raw_lines`]. Invalid UTF-8 is kept in
+/// the line, see [`Line`] for how it is decoded.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{dmesg, Response};
+/// let buf = *b"\x01\x00\x00\x00boot\r\nready\n";
+/// let response = Response::from_bytes(&buf);
+/// let mut lines = dmesg::lines(&response);
+/// assert_eq!(lines.next().unwrap().to_str(), Some("boot"));
+/// assert_eq!(lines.next().unwrap(), "ready");
+/// assert!(lines.next().is_none());
+/// ```
+pub fn lines<'a>(response: &'a Response) -> impl Iterator<Item = Line<'a>> {
+    raw_lines(response).map(Line)
+}
+
+/// Line of a [`Command::Dmesg`](super::Command::Dmesg) response.
+///
+/// [`Display`](core::fmt::Display) replaces each invalid UTF-8 sequence with
+/// U+FFFD, the same as `String::from_utf8_lossy`, so no text around it is
+/// lost.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::dmesg::Line;
+/// let line = Line::new(b"bad\xFFtail");
+/// assert_eq!(line.to_str(), None);
+/// assert!(line.chunks().eq(["bad", "tail"]));
+/// assert_eq!(line.to_string(), "bad\u{FFFD}tail");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a>(&'a [u8]);
+
+impl<'a> Line<'a> {
+    /// Creates a new [`Line`] from raw bytes.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the raw bytes of the line.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Returns the line if it is entirely valid UTF-8.
+    pub fn to_str(&self) -> Option<&'a str> {
+        core::str::from_utf8(self.0).ok()
+    }
+
+    /// Iterate over the valid UTF-8 pieces of the line, skipping invalid
+    /// sequences.
+    pub fn chunks(&self) -> impl Iterator<Item = &'a str> {
+        Utf8Pieces(self.0)
+            .map(|(valid, _)| valid)
+            .filter(|valid| !valid.is_empty())
+    }
+}
+
+impl PartialEq<&str> for Line<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+impl core::fmt::Display for Line<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (valid, invalid) in Utf8Pieces(self.0) {
+            f.write_str(valid)?;
+            if invalid {
+                f.write_str("\u{FFFD}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for Line<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        for (valid, invalid) in Utf8Pieces(self.0) {
+            defmt::write!(f, "{=str}", valid);
+            if invalid {
+                defmt::write!(f, "\u{FFFD}");
+            }
+        }
+    }
+}
+
+/// Splits bytes into valid UTF-8 pieces, each flagged if an invalid sequence
+/// follows it.
+struct Utf8Pieces<'a>(&'a [u8]);
+
+impl<'a> Iterator for Utf8Pieces<'a> {
+    type Item = (&'a str, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        match core::str::from_utf8(self.0) {
+            Ok(valid) => {
+                self.0 = &[];
+                Some((valid, false))
+            }
+            Err(err) => {
+                let (valid, rest) = self.0.split_at(err.valid_up_to());
+                // an incomplete sequence at the end has no error length
+                let invalid_len = err.error_len().unwrap_or(rest.len());
+                self.0 = &rest[invalid_len..];
+
+                // valid up to the error by definition
+                Some((core::str::from_utf8(valid).unwrap_or_default(), true))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let buf = *b"\x01\x00\x00\x00one\ntwo\nthree";
+        let response = Response::from_bytes(&buf);
+        assert!(lines(&response).eq(["one", "two", "three"]));
+    }
+
+    #[test]
+    fn test_lines_trailing_newline() {
+        let buf = *b"\x01\x00\x00\x00one\ntwo\n";
+        let response = Response::from_bytes(&buf);
+        assert!(lines(&response).eq(["one", "two"]));
+
+        let buf = *b"\x01\x00\x00\x00one\n\ntwo\n";
+        let response = Response::from_bytes(&buf);
+        assert!(lines(&response).eq(["one", "", "two"]));
+    }
+
+    #[test]
+    fn test_lines_crlf() {
+        let buf = *b"\x01\x00\x00\x00one\r\ntwo\r\n";
+        let response = Response::from_bytes(&buf);
+        assert!(lines(&response).eq(["one", "two"]));
+    }
+
+    #[test]
+    fn test_lines_empty() {
+        let buf = [0x01, 0x00, 0x00, 0x00];
+        let response = Response::from_bytes(&buf);
+        assert_eq!(lines(&response).count(), 0);
+
+        let buf = *b"\x01\x00\x00\x00\n";
+        let response = Response::from_bytes(&buf);
+        assert_eq!(lines(&response).count(), 0);
+    }
+
+    #[test]
+    fn test_lines_invalid_utf8() {
+        let buf = *b"\x01\x00\x00\x00ok\nbad\xFF\xFEtail\nfine";
+        let response = Response::from_bytes(&buf);
+        assert!(raw_lines(&response).eq([&b"ok"[..], b"bad\xFF\xFEtail", b"fine"]));
+
+        let line = lines(&response).nth(1).unwrap();
+        assert_eq!(line.as_bytes(), b"bad\xFF\xFEtail");
+        assert_eq!(line.to_str(), None);
+        assert!(line.chunks().eq(["bad", "tail"]));
+        assert_eq!(line.to_string(), "bad\u{FFFD}\u{FFFD}tail");
+    }
+
+    #[test]
+    fn test_line_lossy() {
+        // invalid at the start, in the middle and incomplete at the end
+        let line = Line::new(b"\xFFa\xC3\xA9b\xE2\x82");
+        assert!(line.chunks().eq(["a\u{E9}b"]));
+        assert_eq!(line.to_string(), "\u{FFFD}a\u{E9}b\u{FFFD}");
+
+        let line = Line::new(b"plain");
+        assert_eq!(line.to_str(), Some("plain"));
+        assert_eq!(line.to_string(), "plain");
+    }
+}
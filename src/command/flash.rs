@@ -0,0 +1,417 @@
+use super::{Command, Request, Response, Status};
+use crate::le;
+
+/// Flash error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum FlashError {
+    /// Address is not aligned to the flash page size.
+    Unaligned { addr: u32, page_size: u32 },
+    /// Request data is shorter than the target address.
+    Truncated { len: usize },
+}
+
+/// Check that `addr` is aligned to `page_size`.
+///
+/// Use the page size reported by [`BinInfo`](super::bininfo::BinInfo). A
+/// page size of zero never matches.
+pub fn check_aligned(addr: u32, page_size: u32) -> Result<(), FlashError> {
+    if page_size == 0 || addr % page_size != 0 {
+        return Err(FlashError::Unaligned { addr, page_size });
+    }
+
+    Ok(())
+}
+
+/// [`Command::WriteFlashPage`] request.
+///
+/// The request data is the little-endian target address followed by the page
+/// data.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{flash::{self, WriteFlashPage}, Command};
+/// let mut buf = [0; 64];
+/// let page = [0xAA; 16];
+/// let write = WriteFlashPage::new(&mut buf, 1, 0x0000_4000, &page);
+///
+/// let request = write.request();
+/// assert_eq!(request.command(), Command::WriteFlashPage);
+/// assert_eq!(flash::target_addr(request), 0x0000_4000);
+/// assert_eq!(flash::page_data(request), &page);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct WriteFlashPage<'a>(Request<'a>);
+
+impl<'a> WriteFlashPage<'a> {
+    /// Length of the target address at the front of the request data.
+    pub const ADDR_LEN: usize = 4;
+
+    /// Creates a new [`WriteFlashPage`] request.
+    ///
+    /// `buf` must be at least 12 bytes larger than `data` to fit the header
+    /// and target address.
+    pub fn new(buf: &'a mut [u8], tag: u16, target_addr: u32, data: &[u8]) -> Self {
+        let len = Request::HEADER_LEN + Self::ADDR_LEN + data.len();
+
+        // ensure header, address and data will fit in buffer
+        assert!(buf.len() >= len);
+        let buf = &mut buf[..len];
+
+        Request::write_header(buf, Command::WriteFlashPage, tag);
+
+        // write target address then data
+        let body = &mut buf[Request::HEADER_LEN..];
+        le::write_u32(body, 0, target_addr).unwrap();
+        body[Self::ADDR_LEN..].copy_from_slice(data);
+
+        Self(Request::from_bytes(buf))
+    }
+
+    /// Creates a new [`WriteFlashPage`] request after checking the target
+    /// address is aligned to `page_size`.
+    ///
+    /// See [`WriteFlashPage::new`] and [`check_aligned`].
+    pub fn new_aligned(
+        buf: &'a mut [u8],
+        tag: u16,
+        target_addr: u32,
+        data: &[u8],
+        page_size: u32,
+    ) -> Result<Self, FlashError> {
+        check_aligned(target_addr, page_size)?;
+        Ok(Self::new(buf, tag, target_addr, data))
+    }
+
+    /// Returns the target address.
+    pub fn target_addr(&self) -> u32 {
+        target_addr(&self.0)
+    }
+
+    /// Returns the page data.
+    pub fn page_data(&self) -> &[u8] {
+        page_data(&self.0)
+    }
+
+    /// Returns the underlying [`Request`].
+    pub fn request(&self) -> &Request<'a> {
+        &self.0
+    }
+}
+
+/// Get the target address of a [`Command::WriteFlashPage`] request.
+///
+/// Panics if the request data is shorter than 4 bytes.
+pub fn target_addr(request: &Request) -> u32 {
+    le::read_u32(request.data(), 0).unwrap()
+}
+
+/// Get the page data of a [`Command::WriteFlashPage`] request.
+///
+/// Panics if the request data is shorter than 4 bytes.
+pub fn page_data<'a>(request: &'a Request) -> &'a [u8] {
+    &request.data()[WriteFlashPage::ADDR_LEN..]
+}
+
+/// Parse a received [`Command::WriteFlashPage`] request into its target
+/// address and page data.
+///
+/// Returns an error if the request data is shorter than 4 bytes.
+pub fn parse_write_flash_page<'a>(request: &'a Request) -> Result<(u32, &'a [u8]), FlashError> {
+    let data = request.data();
+
+    match le::read_u32(data, 0) {
+        Some(addr) => Ok((addr, &data[WriteFlashPage::ADDR_LEN..])),
+        None => Err(FlashError::Truncated { len: data.len() }),
+    }
+}
+
+/// [`Command::ChecksumPages`] request.
+///
+/// The request data is the little-endian target address followed by the
+/// number of pages to checksum.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ChecksumPagesRequest<'a>(Request<'a>);
+
+impl<'a> ChecksumPagesRequest<'a> {
+    /// Length of the request data.
+    pub const DATA_LEN: usize = 8;
+
+    /// Creates a new [`ChecksumPagesRequest`].
+    ///
+    /// `buf` must be at least 16 bytes to fit the header and request data.
+    pub fn new(buf: &'a mut [u8], tag: u16, target_addr: u32, num_pages: u32) -> Self {
+        let len = Request::HEADER_LEN + Self::DATA_LEN;
+
+        // ensure header and data will fit in buffer
+        assert!(buf.len() >= len);
+        let buf = &mut buf[..len];
+
+        Request::write_header(buf, Command::ChecksumPages, tag);
+        le::write_u32(buf, 8, target_addr).unwrap();
+        le::write_u32(buf, 12, num_pages).unwrap();
+
+        Self(Request::from_bytes(buf))
+    }
+
+    /// Returns the target address.
+    pub fn target_addr(&self) -> u32 {
+        le::read_u32(self.0.data(), 0).unwrap()
+    }
+
+    /// Returns the number of pages.
+    pub fn num_pages(&self) -> u32 {
+        le::read_u32(self.0.data(), 4).unwrap()
+    }
+
+    /// Returns the underlying [`Request`].
+    pub fn request(&self) -> &Request<'a> {
+        &self.0
+    }
+}
+
+/// [`Command::ChecksumPages`] response.
+///
+/// The response data is one little-endian 16-bit checksum per page, so the
+/// number of checksums is `data().len() / 2`.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{ChecksumPagesResponse, Response};
+/// let buf = [0x01, 0x00, 0x00, 0x00, 0xB1, 0x29, 0x34, 0x12];
+/// let response = ChecksumPagesResponse::new(Response::from_bytes(&buf));
+/// assert!(response.checksums().eq([0x29B1, 0x1234]));
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ChecksumPagesResponse<'a>(Response<'a>);
+
+impl<'a> ChecksumPagesResponse<'a> {
+    /// Wraps a [`Response`].
+    pub fn new(response: Response<'a>) -> Self {
+        Self(response)
+    }
+
+    /// Returns an iterator over the page checksums.
+    ///
+    /// A trailing odd byte is ignored.
+    pub fn checksums(&self) -> impl Iterator<Item = u16> + '_ {
+        self.0
+            .data()
+            .chunks_exact(2)
+            .map(|bytes| le::read_u16(bytes, 0).unwrap())
+    }
+
+    /// Returns the underlying [`Response`].
+    pub fn response(&self) -> &Response<'a> {
+        &self.0
+    }
+}
+
+/// Device-side flashing state guard.
+///
+/// Rejects [`Command::WriteFlashPage`] requests until a
+/// [`Command::StartFlash`] request has been seen. A reset request ends the
+/// flashing session.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{flash::FlashGuard, Command, Status};
+/// let mut guard = FlashGuard::new();
+/// assert_eq!(guard.check(Command::WriteFlashPage), Err(Status::Error));
+/// assert_eq!(guard.check(Command::StartFlash), Ok(()));
+/// assert_eq!(guard.check(Command::WriteFlashPage), Ok(()));
+/// ```
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct FlashGuard {
+    started: bool,
+}
+
+impl FlashGuard {
+    /// Create a new guard with flashing not yet started.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check an incoming command against the flashing state.
+    ///
+    /// Returns [`Status::Error`] for a [`Command::WriteFlashPage`] received
+    /// before [`Command::StartFlash`].
+    pub fn check(&mut self, command: Command) -> Result<(), Status> {
+        match command {
+            Command::StartFlash => self.started = true,
+            Command::WriteFlashPage if !self.started => return Err(Status::Error),
+            Command::ResetIntoApp | Command::ResetIntoBootloader => self.started = false,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Check an incoming request against the flashing state.
+    ///
+    /// See [`FlashGuard::check`].
+    pub fn check_request(&mut self, request: &Request) -> Result<(), Status> {
+        self.check(request.command())
+    }
+
+    /// Returns `true` if flashing has been started.
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_flash_page() {
+        let mut buf = [0xFF; 128];
+        let data = [0x55; 64];
+        let write = WriteFlashPage::new(&mut buf, 0x1234, 0x2000_0000, &data);
+
+        assert_eq!(write.target_addr(), 0x2000_0000);
+        assert_eq!(write.page_data(), &data);
+
+        let request = write.request();
+        assert_eq!(request.command(), Command::WriteFlashPage);
+        assert_eq!(request.tag(), 0x1234);
+        assert_eq!(request.reserved(), [0, 0]);
+        assert_eq!(request.len(), 68);
+        assert_eq!(&request.data()[..4], &[0x00, 0x00, 0x00, 0x20]);
+    }
+
+    #[test]
+    fn test_check_aligned() {
+        assert_eq!(check_aligned(0x0000, 256), Ok(()));
+        assert_eq!(check_aligned(0x4000, 256), Ok(()));
+        assert_eq!(check_aligned(0x0100, 256), Ok(()));
+
+        assert_eq!(
+            check_aligned(0x4080, 256),
+            Err(FlashError::Unaligned {
+                addr: 0x4080,
+                page_size: 256
+            })
+        );
+        assert_eq!(
+            check_aligned(0x0001, 256),
+            Err(FlashError::Unaligned {
+                addr: 0x0001,
+                page_size: 256
+            })
+        );
+        assert!(check_aligned(0, 0).is_err());
+    }
+
+    #[test]
+    fn test_write_flash_page_aligned() {
+        let mut buf = [0; 64];
+        let write = WriteFlashPage::new_aligned(&mut buf, 1, 0x4000, &[0xAA; 16], 256).unwrap();
+        assert_eq!(write.target_addr(), 0x4000);
+
+        let mut buf = [0; 64];
+        assert_eq!(
+            WriteFlashPage::new_aligned(&mut buf, 1, 0x4010, &[0xAA; 16], 256).unwrap_err(),
+            FlashError::Unaligned {
+                addr: 0x4010,
+                page_size: 256
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_write_flash_page() {
+        let buf = [
+            0x06, 0x00, 0x00, 0x00, // command
+            0x01, 0x00, 0x00, 0x00, // tag and reserved
+            0x00, 0x40, 0x00, 0x00, // target address
+            0x01, 0x02, 0x03, 0x04, // page data
+        ];
+        let request = Request::from_bytes(&buf);
+
+        assert_eq!(target_addr(&request), 0x4000);
+        assert_eq!(page_data(&request), &[0x01, 0x02, 0x03, 0x04]);
+    }
+
+    #[test]
+    fn test_parse_write_flash_page_checked() {
+        let mut buf = [0; 64];
+        let write = WriteFlashPage::new(&mut buf, 1, 0x0000_4000, &[0xAA; 16]);
+        assert_eq!(
+            parse_write_flash_page(write.request()),
+            Ok((0x0000_4000, &[0xAA; 16][..]))
+        );
+
+        let mut buf = [0; 11];
+        let request = Request::new(&mut buf, Command::WriteFlashPage, 1, &[0x00, 0x40, 0x00]);
+        assert_eq!(
+            parse_write_flash_page(&request),
+            Err(FlashError::Truncated { len: 3 })
+        );
+    }
+
+    #[test]
+    fn test_checksum_pages_request() {
+        let mut buf = [0xFF; 32];
+        let request = ChecksumPagesRequest::new(&mut buf, 3, 0x0000_8000, 16);
+
+        assert_eq!(request.target_addr(), 0x8000);
+        assert_eq!(request.num_pages(), 16);
+        assert_eq!(request.request().command(), Command::ChecksumPages);
+        assert_eq!(request.request().tag(), 3);
+        assert_eq!(request.request().len(), 8);
+    }
+
+    #[test]
+    fn test_checksum_pages_response() {
+        let buf = [
+            0x03, 0x00, 0x00, 0x00, // header
+            0x01, 0x00, // page 0
+            0xCD, 0xAB, // page 1
+            0xFF, 0xFF, // page 2
+            0x00, // trailing
+        ];
+        let response = ChecksumPagesResponse::new(Response::from_bytes(&buf));
+
+        assert_eq!(response.checksums().count(), 3);
+        assert!(response.checksums().eq([0x0001, 0xABCD, 0xFFFF]));
+    }
+
+    #[test]
+    fn test_flash_guard() {
+        let mut guard = FlashGuard::new();
+        assert!(!guard.is_started());
+
+        let mut buf = [0; 16];
+        let write = WriteFlashPage::new(&mut buf, 1, 0, &[0; 4]);
+        assert_eq!(guard.check_request(write.request()), Err(Status::Error));
+
+        let mut buf = [0; Request::HEADER_LEN];
+        let start = Request::start_flash(&mut buf, 2);
+        assert_eq!(guard.check_request(&start), Ok(()));
+        assert!(guard.is_started());
+
+        assert_eq!(guard.check_request(write.request()), Ok(()));
+        assert_eq!(guard.check(Command::ChecksumPages), Ok(()));
+        assert_eq!(guard.check(Command::WriteFlashPage), Ok(()));
+
+        assert_eq!(guard.check(Command::ResetIntoApp), Ok(()));
+        assert!(!guard.is_started());
+        assert_eq!(guard.check(Command::WriteFlashPage), Err(Status::Error));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_write_flash_page_buffer_too_small() {
+        let mut buf = [0; 16];
+        WriteFlashPage::new(&mut buf, 0, 0, &[0; 8]);
+    }
+}
@@ -0,0 +1,80 @@
+use super::{Response, Status};
+use core::str::Utf8Error;
+
+/// Get the info string of a [`Command::Info`](super::Command::Info) response.
+///
+/// A trailing null terminator is trimmed.
+pub fn info_str<'a>(response: &'a Response) -> Result<&'a str, Utf8Error> {
+    let data = response.data();
+    let data = data.strip_suffix(&[0]).unwrap_or(data);
+    core::str::from_utf8(data)
+}
+
+/// [`Command::Info`](super::Command::Info) response.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::info::InfoResponse;
+/// let text = "UF2 Bootloader v1.0";
+/// let mut buf = [0; 23];
+/// let response = InfoResponse::new(&mut buf, 1, text);
+/// assert_eq!(response.info_str(), Ok(text));
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct InfoResponse<'a>(Response<'a>);
+
+impl<'a> InfoResponse<'a> {
+    /// Creates a new successful [`InfoResponse`].
+    ///
+    /// `buf` must be at least 4 bytes larger than `text` to fit the header.
+    pub fn new(buf: &'a mut [u8], tag: u16, text: &str) -> Self {
+        Self(Response::new(buf, tag, Status::Success, 0, text.as_bytes()))
+    }
+
+    /// Returns the info string.
+    pub fn info_str(&self) -> Result<&str, Utf8Error> {
+        info_str(&self.0)
+    }
+
+    /// Returns the underlying [`Response`].
+    pub fn response(&self) -> &Response<'a> {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_info_str() {
+        let buf = [0x01, 0x00, 0x00, 0x00, b'H', b'F', b'2'];
+        let response = Response::from_bytes(&buf);
+        assert_eq!(info_str(&response), Ok("HF2"));
+    }
+
+    #[test]
+    fn test_info_str_null_terminated() {
+        let buf = [0x01, 0x00, 0x00, 0x00, b'H', b'F', b'2', 0x00];
+        let response = Response::from_bytes(&buf);
+        assert_eq!(info_str(&response), Ok("HF2"));
+    }
+
+    #[test]
+    fn test_info_str_invalid() {
+        let buf = [0x01, 0x00, 0x00, 0x00, 0xFF, 0xFE];
+        let response = Response::from_bytes(&buf);
+        assert!(info_str(&response).is_err());
+    }
+
+    #[test]
+    fn test_info_response() {
+        let mut buf = [0; 9];
+        let response = InfoResponse::new(&mut buf, 0x42, "hello");
+        assert_eq!(response.info_str(), Ok("hello"));
+        assert_eq!(response.response().tag(), 0x42);
+        assert_eq!(response.response().status(), Status::Success);
+    }
+}
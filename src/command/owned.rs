@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+
+use super::{Request, Response};
+
+/// Owned request.
+///
+/// Stores a copy of a [`Request`] so it can outlive the buffer it was parsed
+/// from, e.g. to queue it for later processing.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{Command, Request};
+/// let owned = {
+///     let buf = [0x02, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00];
+///     Request::from_bytes(&buf).to_owned()
+/// };
+/// assert_eq!(owned.as_request().command(), Command::Info);
+/// assert_eq!(owned.as_request().tag(), 7);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedRequest(Vec<u8>);
+
+impl OwnedRequest {
+    /// Borrow as a [`Request`].
+    pub fn as_request(&self) -> Request<'_> {
+        Request(&self.0)
+    }
+}
+
+impl From<&Request<'_>> for OwnedRequest {
+    fn from(value: &Request) -> Self {
+        Self(value.0.to_vec())
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for OwnedRequest {
+    fn format(&self, f: defmt::Formatter) {
+        self.as_request().format(f)
+    }
+}
+
+/// Owned response.
+///
+/// Stores a copy of a [`Response`] so it can outlive the buffer it was parsed
+/// from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedResponse(Vec<u8>);
+
+impl OwnedResponse {
+    /// Borrow as a [`Response`].
+    pub fn as_response(&self) -> Response<'_> {
+        Response(&self.0)
+    }
+}
+
+impl From<&Response<'_>> for OwnedResponse {
+    fn from(value: &Response) -> Self {
+        Self(value.0.to_vec())
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for OwnedResponse {
+    fn format(&self, f: defmt::Formatter) {
+        self.as_response().format(f)
+    }
+}
+
+impl Request<'_> {
+    /// Copy into a new [`OwnedRequest`].
+    pub fn to_owned(&self) -> OwnedRequest {
+        OwnedRequest::from(self)
+    }
+}
+
+impl Response<'_> {
+    /// Copy into a new [`OwnedResponse`].
+    pub fn to_owned(&self) -> OwnedResponse {
+        OwnedResponse::from(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{Command, Status};
+
+    #[test]
+    fn test_owned_request() {
+        let owned = {
+            let mut buf = [0; 64];
+            let request =
+                Request::new_with_reserved(&mut buf, Command::ReadWords, 9, [1, 2], &[3; 8]);
+            request.to_owned()
+        };
+
+        let request = owned.as_request();
+        assert_eq!(request.command(), Command::ReadWords);
+        assert_eq!(request.tag(), 9);
+        assert_eq!(request.reserved(), [1, 2]);
+        assert_eq!(request.data(), &[3; 8]);
+        assert_eq!(owned.clone(), OwnedRequest::from(&request));
+    }
+
+    #[test]
+    fn test_owned_response() {
+        let mut queue = alloc::collections::VecDeque::new();
+
+        for tag in 0..3 {
+            let mut buf = [0; 64];
+            let response = Response::new(&mut buf, tag, Status::Success, 0, &[tag as u8]);
+            queue.push_back(response.to_owned());
+        }
+
+        for tag in 0..3 {
+            let owned = queue.pop_front().unwrap();
+            let response = owned.as_response();
+            assert_eq!(response.tag(), tag);
+            assert_eq!(response.result(), Ok(&[tag as u8][..]));
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use super::{Command, CommandError, Request, Response};
+use crate::le;
+
+/// [`Command::ReadWords`] request.
+///
+/// The request data is the little-endian target address followed by the
+/// number of words to read.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ReadWordsRequest<'a>(Request<'a>);
+
+impl<'a> ReadWordsRequest<'a> {
+    /// Length of the request data.
+    pub const DATA_LEN: usize = 8;
+
+    /// Creates a new [`ReadWordsRequest`].
+    ///
+    /// `buf` must be at least 16 bytes to fit the header and request data.
+    pub fn new(buf: &'a mut [u8], tag: u16, addr: u32, num_words: u32) -> Self {
+        let len = Request::HEADER_LEN + Self::DATA_LEN;
+
+        // ensure header and data will fit in buffer
+        assert!(buf.len() >= len);
+        let buf = &mut buf[..len];
+
+        Request::write_header(buf, Command::ReadWords, tag);
+        le::write_u32(buf, 8, addr).unwrap();
+        le::write_u32(buf, 12, num_words).unwrap();
+
+        Self(Request::from_bytes(buf))
+    }
+
+    /// Returns the target address.
+    pub fn addr(&self) -> u32 {
+        le::read_u32(self.0.data(), 0).unwrap()
+    }
+
+    /// Returns the number of words to read.
+    pub fn num_words(&self) -> u32 {
+        le::read_u32(self.0.data(), 4).unwrap()
+    }
+
+    /// Returns the underlying [`Request`].
+    pub fn request(&self) -> &Request<'a> {
+        &self.0
+    }
+}
+
+/// Parse a received [`Command::ReadWords`] request into its target address
+/// and number of words.
+///
+/// Returns an error if the request data is shorter than 8 bytes.
+pub fn parse_read_words(request: &Request) -> Result<(u32, u32), CommandError> {
+    let data = request.data();
+
+    match (le::read_u32(data, 0), le::read_u32(data, 4)) {
+        (Some(addr), Some(num_words)) => Ok((addr, num_words)),
+        _ => Err(CommandError::DataTooShort {
+            needed: ReadWordsRequest::DATA_LEN,
+            got: data.len(),
+        }),
+    }
+}
+
+/// [`Command::ReadWords`] response.
+///
+/// The response data is a sequence of little-endian words.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{ReadWordsResponse, Response};
+/// let buf = [0x01, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12];
+/// let response = ReadWordsResponse::new(Response::from_bytes(&buf));
+/// let mut words = response.words();
+/// assert_eq!(words.next(), Some(0x12345678));
+/// assert_eq!(words.next(), None);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ReadWordsResponse<'a>(Response<'a>);
+
+impl<'a> ReadWordsResponse<'a> {
+    /// Wraps a [`Response`].
+    pub fn new(response: Response<'a>) -> Self {
+        Self(response)
+    }
+
+    /// Returns an iterator over the words.
+    ///
+    /// Trailing bytes that don't make up a whole word are ignored.
+    pub fn words(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0
+            .data()
+            .chunks_exact(4)
+            .map(|bytes| le::read_u32(bytes, 0).unwrap())
+    }
+
+    /// Returns the underlying [`Response`].
+    pub fn response(&self) -> &Response<'a> {
+        &self.0
+    }
+}
+
+/// [`Command::WriteWords`] request.
+///
+/// The request data is the little-endian target address and number of words
+/// followed by the words themselves.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::WriteWordsRequest;
+/// let words = [0xDEAD_BEEF, 0x1234_5678];
+/// let mut buf = [0; 24];
+/// let request = WriteWordsRequest::new(&mut buf, 1, 0x2000_0000, &words);
+/// assert_eq!(request.num_words(), 2);
+/// assert!(request.words().eq(words));
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct WriteWordsRequest<'a>(Request<'a>);
+
+impl<'a> WriteWordsRequest<'a> {
+    /// Length of the address and word count at the front of the request data.
+    pub const FIELDS_LEN: usize = 8;
+
+    /// Creates a new [`WriteWordsRequest`].
+    ///
+    /// `buf` must be exactly `16 + words.len() * 4` bytes to fit the header,
+    /// fields and words.
+    pub fn new(buf: &'a mut [u8], tag: u16, addr: u32, words: &[u32]) -> Self {
+        // ensure header, fields and words will fit in buffer
+        assert!(buf.len() == Request::HEADER_LEN + Self::FIELDS_LEN + words.len() * 4);
+
+        Request::write_header(buf, Command::WriteWords, tag);
+        le::write_u32(buf, 8, addr).unwrap();
+        le::write_u32(buf, 12, words.len() as u32).unwrap();
+
+        for (bytes, word) in buf[16..].chunks_exact_mut(4).zip(words) {
+            le::write_u32(bytes, 0, *word).unwrap();
+        }
+
+        Self(Request::from_bytes(buf))
+    }
+
+    /// Wraps a received [`Request`].
+    ///
+    /// Panics if the request data is shorter than 8 bytes.
+    pub fn from_request(request: Request<'a>) -> Self {
+        assert!(request.len() >= Self::FIELDS_LEN);
+        Self(request)
+    }
+
+    /// Returns the target address.
+    pub fn addr(&self) -> u32 {
+        le::read_u32(self.0.data(), 0).unwrap()
+    }
+
+    /// Returns the number of words to write.
+    pub fn num_words(&self) -> u32 {
+        le::read_u32(self.0.data(), 4).unwrap()
+    }
+
+    /// Returns an iterator over the words.
+    ///
+    /// Trailing bytes that don't make up a whole word are ignored.
+    pub fn words(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.data()[Self::FIELDS_LEN..]
+            .chunks_exact(4)
+            .map(|bytes| le::read_u32(bytes, 0).unwrap())
+    }
+
+    /// Returns the underlying [`Request`].
+    pub fn request(&self) -> &Request<'a> {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_words_request() {
+        let mut buf = [0xFF; 64];
+        let request = ReadWordsRequest::new(&mut buf, 0x42, 0x2000_0000, 4);
+
+        assert_eq!(request.addr(), 0x2000_0000);
+        assert_eq!(request.num_words(), 4);
+        assert_eq!(request.request().command(), Command::ReadWords);
+        assert_eq!(request.request().tag(), 0x42);
+        assert_eq!(request.request().len(), 8);
+    }
+
+    #[test]
+    fn test_parse_read_words() {
+        let mut buf = [0; 16];
+        let request = ReadWordsRequest::new(&mut buf, 1, 0x2000_0000, 4);
+        assert_eq!(parse_read_words(request.request()), Ok((0x2000_0000, 4)));
+
+        let mut buf = [0; 12];
+        let request = Request::new(&mut buf, Command::ReadWords, 1, &[0x00, 0x00, 0x00, 0x20]);
+        assert_eq!(
+            parse_read_words(&request),
+            Err(CommandError::DataTooShort { needed: 8, got: 4 })
+        );
+    }
+
+    #[test]
+    fn test_write_words_request() {
+        let words = [0x0000_0001, 0xDEAD_BEEF, 0xFFFF_FFFF];
+        let mut buf = [0; 28];
+        let request = WriteWordsRequest::new(&mut buf, 7, 0x2000_0100, &words);
+
+        assert_eq!(request.request().command(), Command::WriteWords);
+        assert_eq!(request.request().tag(), 7);
+        assert_eq!(&buf[16..20], &[0x01, 0x00, 0x00, 0x00]);
+
+        let request = WriteWordsRequest::from_request(Request::from_bytes(&buf));
+        assert_eq!(request.addr(), 0x2000_0100);
+        assert_eq!(request.num_words(), 3);
+        assert!(request.words().eq(words));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_write_words_request_wrong_size() {
+        let mut buf = [0; 32];
+        WriteWordsRequest::new(&mut buf, 0, 0, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_read_words_response() {
+        let buf = [
+            0x42, 0x00, 0x00, 0x00, // header
+            0x01, 0x00, 0x00, 0x00, // word 0
+            0xEF, 0xBE, 0xAD, 0xDE, // word 1
+            0xFF, 0xFF, 0xFF, 0xFF, // word 2
+        ];
+        let response = ReadWordsResponse::new(Response::from_bytes(&buf));
+
+        let mut words = [0; 3];
+        let mut count = 0;
+        for (i, word) in response.words().enumerate() {
+            words[i] = word;
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        assert_eq!(words, [0x0000_0001, 0xDEAD_BEEF, 0xFFFF_FFFF]);
+    }
+
+    #[test]
+    fn test_read_words_response_partial_word() {
+        let buf = [0x42, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00];
+        let response = ReadWordsResponse::new(Response::from_bytes(&buf));
+
+        let mut words = response.words();
+        assert_eq!(words.next(), Some(1));
+        assert_eq!(words.next(), None);
+    }
+}
@@ -0,0 +1,132 @@
+//! [`Arbitrary`] implementations for fuzzing and property testing.
+//!
+//! Generated values are always valid: packets never declare more payload than
+//! they carry and commands and statuses are normalized the same way parsing
+//! does, so a value survives a round trip through its wire representation.
+
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+use crate::command::{Command, Request, Status};
+use crate::{wire, Packet, PacketKind};
+
+impl<'a> Arbitrary<'a> for PacketKind {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::from_header(u8::arbitrary(u)?))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, Some(1))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Packet<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let header = u.peek_bytes(1).ok_or(Error::NotEnoughData)?[0];
+        let len = (header as usize & 0b00111111) + wire::HEADER_LEN;
+
+        Ok(Self::from_bytes(u.bytes(len)?))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (wire::HEADER_LEN, Some(wire::REPORT_LEN))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Command {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // a uniform id would almost never hit a standard command
+        if u.ratio(3u8, 4u8)? {
+            Ok(*u.choose(Command::ALL)?)
+        } else {
+            Ok(Self::from(u32::arbitrary(u)?))
+        }
+    }
+}
+
+impl<'a> Arbitrary<'a> for Status {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::from(u8::arbitrary(u)?))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, Some(1))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Request<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let data_len = u.arbitrary_len::<u8>()?;
+        let data_len = data_len.min(u.len().saturating_sub(Self::HEADER_LEN));
+
+        Ok(Self::from_bytes(u.bytes(Self::HEADER_LEN + data_len)?))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (Self::HEADER_LEN, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random input.
+    fn input(seed: u32) -> [u8; 512] {
+        let mut state = seed;
+        core::array::from_fn(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as u8
+        })
+    }
+
+    #[test]
+    fn test_packet_round_trip() {
+        for seed in 0..64 {
+            let data = input(seed);
+            let mut u = Unstructured::new(&data);
+
+            while let Ok(packet) = Packet::arbitrary(&mut u) {
+                assert_eq!(packet.validate(), Ok(()));
+                assert_eq!(Packet::from_bytes(packet.as_bytes()), packet);
+                assert_eq!(PacketKind::from(packet.header()), packet.kind());
+            }
+        }
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        for seed in 0..64 {
+            let data = input(seed);
+            let mut u = Unstructured::new(&data);
+
+            while let Ok(request) = Request::arbitrary(&mut u) {
+                let mut buf = [0; 512];
+                let built = Request::new_with_reserved(
+                    &mut buf[..request.total_len()],
+                    request.command(),
+                    request.tag(),
+                    request.reserved(),
+                    request.data(),
+                );
+                assert!(built.eq_bytes(&request));
+            }
+        }
+    }
+
+    #[test]
+    fn test_enum_round_trip() {
+        for seed in 0..64 {
+            let data = input(seed);
+            let mut u = Unstructured::new(&data);
+
+            let command = Command::arbitrary(&mut u).unwrap();
+            assert_eq!(Command::from(u32::from(command)), command);
+
+            let status = Status::arbitrary(&mut u).unwrap();
+            assert_eq!(Status::from(u8::from(status)), status);
+
+            let bits = PacketKind::arbitrary(&mut u).unwrap().as_u8();
+            assert_eq!(PacketKind::from_header(bits).as_u8(), bits);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+#[cfg(feature = "embedded-io-async-06")]
+use crate::wire;
+use crate::Packet;
+#[cfg(feature = "embedded-io-06")]
+use embedded_io::Write;
+#[cfg(feature = "embedded-io-async-06")]
+use embedded_io_async::{Read, ReadExactError};
+
+/// Packet writer over an [`embedded_io::Write`] transport.
+///
+/// Each packet is written as a zero-padded [`Packet::REPORT_LEN`] byte report.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::{io::PacketWriter, Packet};
+/// let mut writer = PacketWriter::new(Vec::new());
+/// let packet = Packet::from_bytes(&[0x82, 0x01, 0x02]);
+/// writer.write(&packet).unwrap();
+/// assert_eq!(writer.into_inner().len(), 64);
+/// ```
+#[cfg(feature = "embedded-io-06")]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct PacketWriter<W> {
+    writer: W,
+}
+
+#[cfg(feature = "embedded-io-06")]
+impl<W: Write> PacketWriter<W> {
+    /// Create a new packet writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Write a packet as a padded report.
+    pub fn write(&mut self, packet: &Packet) -> Result<(), W::Error> {
+        let mut report = [0; Packet::REPORT_LEN];
+        packet.write_report(&mut report);
+        self.writer.write_all(&report)
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> Result<(), W::Error> {
+        self.writer.flush()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consumes the packet writer, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Read a packet from an [`embedded_io_async::Read`] transport.
+///
+/// Reads a full [`Packet::REPORT_LEN`] byte report into `buf`, looping over
+/// short reads, then parses the packet from the front of it.
+#[cfg(feature = "embedded-io-async-06")]
+pub async fn read_packet<'a, R: Read>(
+    reader: &mut R,
+    buf: &'a mut [u8; wire::REPORT_LEN],
+) -> Result<Packet<'a>, ReadExactError<R::Error>> {
+    reader.read_exact(buf).await?;
+    Ok(Packet::from_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PacketKind;
+
+    #[test]
+    #[cfg(feature = "embedded-io-06")]
+    fn test_packet_writer() {
+        let mut writer = PacketWriter::new(Vec::new());
+
+        let mut buf = [0; 64];
+        let packet = Packet::new(&mut buf, PacketKind::StdOut, &[1, 2, 3]);
+        writer.write(&packet).unwrap();
+
+        let mut buf = [0; 64];
+        let packet = Packet::new(&mut buf, PacketKind::CommandFinal, &[4]);
+        writer.write(&packet).unwrap();
+
+        let written = writer.into_inner();
+        assert_eq!(written.len(), 128);
+        assert_eq!(&written[..4], &[0x83, 1, 2, 3]);
+        assert!(written[4..64].iter().all(|&b| b == 0));
+        assert_eq!(&written[64..66], &[0x41, 4]);
+        assert!(written[66..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-io-06")]
+    fn test_packet_writer_error() {
+        let mut storage = [0; 32];
+        let mut writer = PacketWriter::new(&mut storage[..]);
+
+        let packet = Packet::from_bytes(&[0x80]);
+        assert!(writer.write(&packet).is_err());
+    }
+
+    /// Reader returning at most `chunk` bytes per read.
+    #[cfg(feature = "embedded-io-async-06")]
+    struct ChunkedReader<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    #[cfg(feature = "embedded-io-async-06")]
+    impl embedded_io_async::ErrorType for ChunkedReader<'_> {
+        type Error = core::convert::Infallible;
+    }
+
+    #[cfg(feature = "embedded-io-async-06")]
+    impl Read for ChunkedReader<'_> {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let len = buf.len().min(self.chunk).min(self.data.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    #[cfg(feature = "embedded-io-async-06")]
+    fn block_on<F: core::future::Future>(future: F) -> F::Output {
+        use core::task::{RawWaker, RawWakerVTable, Waker};
+
+        // `Waker::noop` needs a newer compiler than the crate supports
+        const VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(core::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        // SAFETY: the vtable functions never touch the data pointer
+        let waker = unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) };
+
+        let mut future = core::pin::pin!(future);
+        let mut cx = core::task::Context::from_waker(&waker);
+        loop {
+            if let core::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-io-async-06")]
+    fn test_read_packet() {
+        let mut data = [0; 128];
+        data[..4].copy_from_slice(&[0x83, 1, 2, 3]);
+        data[64..66].copy_from_slice(&[0xC1, 4]);
+
+        let mut reader = ChunkedReader {
+            data: &data,
+            chunk: 10,
+        };
+
+        let mut buf = [0; 64];
+        let packet = block_on(read_packet(&mut reader, &mut buf)).unwrap();
+        assert!(packet.kind() == PacketKind::StdOut);
+        assert_eq!(packet.data(), &[1, 2, 3]);
+
+        let mut buf = [0; 64];
+        let packet = block_on(read_packet(&mut reader, &mut buf)).unwrap();
+        assert!(packet.kind() == PacketKind::StdErr);
+        assert_eq!(packet.data(), &[4]);
+
+        let mut buf = [0; 64];
+        assert!(matches!(
+            block_on(read_packet(&mut reader, &mut buf)),
+            Err(ReadExactError::UnexpectedEof)
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+//! Little-endian field access.
+//!
+//! All HF2 multi-byte fields are little-endian. These helpers return `None`
+//! instead of panicking when the field doesn't fit in the buffer.
+
+/// Read a `u16` at `offset`.
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Read a `u32` at `offset`.
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Write a `u16` at `offset`.
+pub(crate) fn write_u16(buf: &mut [u8], offset: usize, value: u16) -> Option<()> {
+    let bytes = buf.get_mut(offset..offset.checked_add(2)?)?;
+    bytes.copy_from_slice(&value.to_le_bytes());
+    Some(())
+}
+
+/// Write a `u32` at `offset`.
+pub(crate) fn write_u32(buf: &mut [u8], offset: usize, value: u32) -> Option<()> {
+    let bytes = buf.get_mut(offset..offset.checked_add(4)?)?;
+    bytes.copy_from_slice(&value.to_le_bytes());
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let data = [0x01, 0x02, 0x03, 0x04, 0x05];
+        assert_eq!(read_u16(&data, 0), Some(0x0201));
+        assert_eq!(read_u16(&data, 3), Some(0x0504));
+        assert_eq!(read_u32(&data, 0), Some(0x04030201));
+        assert_eq!(read_u32(&data, 1), Some(0x05040302));
+    }
+
+    #[test]
+    fn test_read_out_of_range() {
+        let data = [0x01, 0x02, 0x03, 0x04];
+        assert_eq!(read_u16(&data, 3), None);
+        assert_eq!(read_u16(&data, 4), None);
+        assert_eq!(read_u32(&data, 1), None);
+        assert_eq!(read_u32(&[], 0), None);
+        assert_eq!(read_u32(&data, usize::MAX), None);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut buf = [0; 6];
+        assert_eq!(write_u16(&mut buf, 0, 0x0201), Some(()));
+        assert_eq!(write_u32(&mut buf, 2, 0x06050403), Some(()));
+        assert_eq!(buf, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+    }
+
+    #[test]
+    fn test_write_out_of_range() {
+        let mut buf = [0; 4];
+        assert_eq!(write_u16(&mut buf, 3, 0xFFFF), None);
+        assert_eq!(write_u32(&mut buf, 1, 0xFFFF_FFFF), None);
+        assert_eq!(write_u32(&mut buf, usize::MAX, 0), None);
+        assert_eq!(buf, [0; 4]);
+    }
+}
@@ -1,11 +1,52 @@
 #![cfg_attr(not(test), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod checksum;
+pub mod codec;
 pub mod command;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+#[cfg(any(feature = "embedded-io-06", feature = "embedded-io-async-06"))]
+pub mod io;
+mod le;
+pub mod output;
+#[cfg(feature = "heapless-08")]
+mod owned;
+pub mod reassembly;
+pub mod session;
+pub mod wire;
+
+#[cfg(feature = "heapless-08")]
+pub use owned::OwnedPacket;
+
+/// Number of packets needed to send a command payload of `payload_len` bytes.
+///
+/// An empty payload still takes a single [`PacketKind::CommandFinal`] packet.
+pub fn packets_for(payload_len: usize) -> usize {
+    payload_len.div_ceil(Packet::MAX_LEN).max(1)
+}
+
+/// Number of bytes needed to send a command payload of `payload_len` bytes
+/// as padded HID reports.
+pub fn wire_size(payload_len: usize) -> usize {
+    packets_for(payload_len) * Packet::REPORT_LEN
+}
+
+/// Get the kind of a packet from its header byte.
+///
+/// Only looks at the first byte, so a receive loop can route a report by kind
+/// before deciding whether to parse it. Same as [`PacketKind::from_header`].
+pub const fn kind_of(header: u8) -> PacketKind {
+    PacketKind::from_header(header)
+}
 
 /// Packet kind.
 ///
-/// Stored in the top two bits of the first byte of the packet.
-#[derive(Debug, PartialEq, Eq)]
+/// Stored in the top two bits of the first byte of the packet, so these four
+/// kinds are all there can be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 #[repr(u8)]
 pub enum PacketKind {
@@ -15,9 +56,25 @@ pub enum PacketKind {
     StdErr = 0xC0,
 }
 
-impl From<u8> for PacketKind {
-    fn from(value: u8) -> Self {
-        match value & 0b11000000 {
+impl PacketKind {
+    /// Mask of the kind bits in the header byte.
+    pub const MASK: u8 = 0b1100_0000;
+
+    /// Returns every kind, in header bit order.
+    pub const fn all() -> [Self; 4] {
+        [
+            Self::CommandInner,
+            Self::CommandFinal,
+            Self::StdOut,
+            Self::StdErr,
+        ]
+    }
+
+    /// Get the kind from a packet header byte.
+    ///
+    /// The length bits are ignored.
+    pub const fn from_header(byte: u8) -> Self {
+        match byte & Self::MASK {
             0x00 => Self::CommandInner,
             0x40 => Self::CommandFinal,
             0x80 => Self::StdOut,
@@ -26,6 +83,60 @@ impl From<u8> for PacketKind {
             _ => unreachable!(),
         }
     }
+
+    /// Get the kind from a packet header byte, checking the length it
+    /// declares against `available` bytes of buffer including the header.
+    ///
+    /// Returns [`PacketError::Truncated`] if the packet would not fit.
+    pub fn try_from_header(byte: u8, available: usize) -> Result<Self, PacketError> {
+        let len = (byte as usize & 0b00111111) + wire::HEADER_LEN;
+
+        if len > available {
+            return Err(PacketError::Truncated { len, available });
+        }
+
+        Ok(Self::from_header(byte))
+    }
+
+    /// Strict conversion that treats the whole byte as the kind value.
+    ///
+    /// Unlike [`PacketKind::from_header`], fails if any length bits are set.
+    /// This is not a `TryFrom` impl as that is already provided through
+    /// `From`.
+    pub const fn try_from_exact(byte: u8) -> Result<Self, InvalidKind> {
+        if byte & !Self::MASK != 0 {
+            return Err(InvalidKind(byte));
+        }
+
+        Ok(Self::from_header(byte))
+    }
+
+    /// Returns the kind bits as they appear in the header byte.
+    pub const fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns `true` for [`PacketKind::CommandInner`] and
+    /// [`PacketKind::CommandFinal`].
+    pub fn is_command(&self) -> bool {
+        matches!(self, Self::CommandInner | Self::CommandFinal)
+    }
+
+    /// Returns `true` for [`PacketKind::CommandFinal`].
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::CommandFinal)
+    }
+
+    /// Returns `true` for [`PacketKind::StdOut`] and [`PacketKind::StdErr`].
+    pub fn is_output(&self) -> bool {
+        matches!(self, Self::StdOut | Self::StdErr)
+    }
+}
+
+impl From<u8> for PacketKind {
+    fn from(value: u8) -> Self {
+        Self::from_header(value)
+    }
 }
 
 impl From<&Packet<'_>> for PacketKind {
@@ -34,6 +145,25 @@ impl From<&Packet<'_>> for PacketKind {
     }
 }
 
+/// Packet error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum PacketError {
+    /// Payload is longer than [`Packet::MAX_LEN`].
+    DataTooLong { len: usize },
+    /// Buffer is too small to hold the header and payload.
+    BufferTooSmall { needed: usize, got: usize },
+    /// Header declares a packet longer than the available bytes.
+    Truncated { len: usize, available: usize },
+    /// Message is longer than the device's maximum message size.
+    MessageTooLong { len: usize, max: usize },
+}
+
+/// Byte that is not a bare [`PacketKind`] value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct InvalidKind(pub u8);
+
 /// Packet view into a byte slice.
 ///
 /// # Example
@@ -47,19 +177,77 @@ impl From<&Packet<'_>> for PacketKind {
 /// println!("Output: {}", string); // Prints: "Output: Hello"
 /// ```
 #[derive(Debug)]
-#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub struct Packet<'a>(&'a [u8]);
 
+/// Packets are equal if their kind and payload are equal, regardless of any
+/// trailing bytes in the backing buffer.
+impl PartialEq for Packet<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind() == other.kind() && self.data() == other.data()
+    }
+}
+
+impl Eq for Packet<'_> {}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for Packet<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Packet {{ kind: {}, data: {=[u8]:02x} }}",
+            self.kind(),
+            self.data()
+        )
+    }
+}
+
 impl<'a> Packet<'a> {
     /// Maximum payload length.
-    pub const MAX_LEN: usize = 63;
+    pub const MAX_LEN: usize = wire::MAX_PAYLOAD;
+
+    /// HID report length.
+    pub const REPORT_LEN: usize = wire::REPORT_LEN;
 
     /// Create a new packet.
     ///
     /// `buf` must be at least one byte longer than `data`.
+    ///
+    /// Panics if `data` is too long or `buf` is too small. See
+    /// [`Packet::try_new`] for a non-panicking version.
     pub fn new(buf: &'a mut [u8], kind: PacketKind, data: &[u8]) -> Self {
-        assert!(data.len() <= 63); // not too much data
-        assert!(buf.len() >= data.len() + 1); // enough space for header
+        match Self::try_new(buf, kind, data) {
+            Ok(packet) => packet,
+            Err(err) => panic!("invalid packet: {:?}", err),
+        }
+    }
+
+    /// Create a new packet, also returning the number of bytes of `buf` it
+    /// occupies.
+    ///
+    /// Panics under the same conditions as [`Packet::new`].
+    pub fn write(buf: &'a mut [u8], kind: PacketKind, data: &[u8]) -> (Self, usize) {
+        let packet = Self::new(buf, kind, data);
+        let len = packet.len();
+        (packet, len)
+    }
+
+    /// Try to create a new packet.
+    ///
+    /// Returns an error if `data` is longer than [`Packet::MAX_LEN`] or if
+    /// `buf` is not at least one byte longer than `data`.
+    pub fn try_new(buf: &'a mut [u8], kind: PacketKind, data: &[u8]) -> Result<Self, PacketError> {
+        // not too much data and no overlap with the kind bits
+        if data.len() > Self::MAX_LEN || data.len() & !0b00111111 != 0 {
+            return Err(PacketError::DataTooLong { len: data.len() });
+        }
+
+        // enough space for header
+        if buf.len() <= data.len() {
+            return Err(PacketError::BufferTooSmall {
+                needed: data.len() + wire::HEADER_LEN,
+                got: buf.len(),
+            });
+        }
 
         // copy data into buffer
         buf[1..][0..data.len()].copy_from_slice(data);
@@ -67,27 +255,117 @@ impl<'a> Packet<'a> {
         buf[0] = 0; // ensure bits are cleared
         buf[0] |= data.len() as u8;
         buf[0] |= kind as u8;
-        Self(buf)
+        Ok(Self(buf))
     }
 
     /// Create a new packet from a buffer.
     ///
-    /// Panics if `buf` is larger than 64 bytes or less than 1 byte in size.
+    /// Only the length declared in the header is taken from the front of
+    /// `buf`, so it may be a larger arena holding more data after the packet.
+    ///
+    /// Panics if `buf` is less than 1 byte in size or shorter than the length
+    /// declared in the header. Use [`Packet::try_from_bytes`] when parsing
+    /// untrusted or possibly truncated transfers.
     pub fn from_bytes(buf: &'a [u8]) -> Self {
-        assert!(buf.len() > 0);
-        assert!(buf.len() <= 64);
+        Self::from_bytes_sized(buf, usize::MAX)
+    }
+
+    /// Create a new packet from a received HID report.
+    ///
+    /// Some HID stacks prefix reports with a report ID byte, shifting the
+    /// packet by one byte. Set `report_id` to skip that byte; the value of the
+    /// report ID itself is not checked.
+    ///
+    /// Panics under the same conditions as [`Packet::from_bytes`] once the
+    /// report ID is skipped.
+    pub fn from_report(buf: &'a [u8], report_id: bool) -> Self {
+        if report_id {
+            Self::from_bytes(&buf[1..])
+        } else {
+            Self::from_bytes(buf)
+        }
+    }
+
+    /// Create a new packet from a buffer of a negotiated report size.
+    ///
+    /// Panics if `buf` is larger than `report_len` bytes, less than 1 byte in
+    /// size or shorter than the length declared in the header.
+    pub fn from_bytes_sized(buf: &'a [u8], report_len: usize) -> Self {
+        assert!(!buf.is_empty());
+        assert!(buf.len() <= report_len);
 
         let len = buf[0] as usize & 0b00111111;
-        let len = len + 1; // compensate for header
+        let len = len + wire::HEADER_LEN;
+        assert!(
+            len <= buf.len(),
+            "packet header declares more data than available"
+        );
 
         Self(&buf[0..len])
     }
 
-    /// Returns the length of the packet including the header byte.
+    /// Create a new packet from a buffer in a `const` context.
+    ///
+    /// See the [`packet!`] macro to also compute the header byte at compile
+    /// time.
+    ///
+    /// Like [`Packet::from_bytes`], `buf` may be larger than the packet.
+    ///
+    /// Panics if `buf` is less than 1 byte in size or shorter than the length
+    /// declared in the header.
+    pub const fn from_bytes_const(buf: &'a [u8]) -> Self {
+        assert!(!buf.is_empty());
+
+        let len = (buf[0] as usize & 0b00111111) + wire::HEADER_LEN;
+        assert!(len <= buf.len());
+
+        Self(buf.split_at(len).0)
+    }
+
+    /// Try to create a new packet from a buffer.
+    ///
+    /// Returns an error if `buf` is empty or shorter than the length declared
+    /// in the header. Any bytes beyond the declared length are ignored.
+    pub fn try_from_bytes(buf: &'a [u8]) -> Result<Self, PacketError> {
+        if buf.is_empty() {
+            return Err(PacketError::BufferTooSmall { needed: 1, got: 0 });
+        }
+
+        let packet = Self(buf);
+        packet.validate()?;
+
+        Ok(Self(&buf[..packet.len()]))
+    }
+
+    /// Check that the length declared in the header is consistent with the
+    /// backing buffer.
+    pub fn validate(&self) -> Result<(), PacketError> {
+        PacketKind::try_from_header(self.header(), self.0.len())?;
+
+        Ok(())
+    }
+
+    /// Returns the length of the packet *including* the header byte.
     ///
-    /// Use `.data().len()` to get the size of just the payload.
+    /// This is the number of bytes the packet occupies on the wire, use
+    /// [`Packet::payload_len`] to get the size of just the payload.
     pub fn len(&self) -> usize {
-        (self.0[0] as usize & 0b00111111) + 1
+        self.payload_len() + wire::HEADER_LEN
+    }
+
+    /// Returns the length of the payload as declared in the header.
+    pub fn payload_len(&self) -> usize {
+        self.0[0] as usize & 0b00111111
+    }
+
+    /// Returns `true` if the packet has no payload.
+    pub fn is_empty(&self) -> bool {
+        self.payload_len() == 0
+    }
+
+    /// Returns the raw header byte, as seen on the wire.
+    pub fn header(&self) -> u8 {
+        self.0[0]
     }
 
     /// Get the kind of packet.
@@ -99,33 +377,1216 @@ impl<'a> Packet<'a> {
     pub fn data(&self) -> &[u8] {
         &self.0[1..self.len()]
     }
+
+    /// Access the packet data, or `None` if the header declares more data
+    /// than the packet holds.
+    pub fn try_data(&self) -> Option<&[u8]> {
+        self.0.get(1..self.len())
+    }
+
+    /// Access the full packet including the header byte.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0[..self.len()]
+    }
+
+    /// Write the full packet including the header byte as uppercase ASCII hex
+    /// into `out`.
+    ///
+    /// Returns the number of bytes written, two per packet byte, or an error if
+    /// `out` is too small.
+    pub fn write_hex(&self, out: &mut [u8]) -> Result<usize, PacketError> {
+        const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+        let bytes = self.as_bytes();
+        let needed = bytes.len() * 2;
+
+        if out.len() < needed {
+            return Err(PacketError::BufferTooSmall {
+                needed,
+                got: out.len(),
+            });
+        }
+
+        for (byte, hex) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+            hex[0] = DIGITS[(byte >> 4) as usize];
+            hex[1] = DIGITS[(byte & 0x0F) as usize];
+        }
+
+        Ok(needed)
+    }
+
+    /// Write the packet into a zero-padded HID report.
+    ///
+    /// Returns the number of meaningful bytes written.
+    pub fn write_report(&self, report: &mut [u8; wire::REPORT_LEN]) -> usize {
+        self.write_report_sized(report)
+    }
+
+    /// Write the packet into a zero-padded HID report prefixed with a report
+    /// ID byte, as expected by some HID stacks. The HF2 report ID is `0x00`.
+    ///
+    /// Returns the number of meaningful bytes written, including the report
+    /// ID byte.
+    pub fn write_report_with_id(
+        &self,
+        report: &mut [u8; wire::REPORT_LEN + 1],
+        report_id: u8,
+    ) -> usize {
+        report[0] = report_id;
+        self.write_report_sized(&mut report[1..]) + 1
+    }
+
+    /// Write the packet into a zero-padded HID report of a negotiated size.
+    ///
+    /// The report size is the length of `report`. Returns the number of
+    /// meaningful bytes written.
+    ///
+    /// Panics if the packet does not fit in `report`.
+    pub fn write_report_sized(&self, report: &mut [u8]) -> usize {
+        let bytes = self.as_bytes();
+        assert!(bytes.len() <= report.len());
+
+        report[..bytes.len()].copy_from_slice(bytes);
+        report[bytes.len()..].fill(0);
+        bytes.len()
+    }
+
+    /// Iterate over the packets in a buffer of concatenated HID reports.
+    ///
+    /// See [`PacketIter`].
+    pub fn iter(buf: &[u8]) -> PacketIter<'_> {
+        PacketIter::new(buf, Stride::Report)
+    }
+
+    /// Iterate over the packets in a buffer of concatenated HID reports of a
+    /// negotiated size.
+    ///
+    /// See [`PacketIter`].
+    pub fn iter_sized(buf: &[u8], report_len: usize) -> PacketIter<'_> {
+        PacketIter::new(buf, Stride::Sized(report_len))
+    }
+
+    /// Split a command payload into a sequence of packets.
+    ///
+    /// See [`PacketFragments`].
+    pub fn fragments(data: &[u8]) -> PacketFragments<'_> {
+        PacketFragments {
+            data,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// Split a command payload into a sequence of packets, refusing messages
+    /// longer than a device's `max_message_size`.
+    ///
+    /// The limit is typically taken from
+    /// [`BinInfo::max_message_size`](command::bininfo::BinInfo::max_message_size).
+    /// Returns [`PacketError::MessageTooLong`] if `data` exceeds it.
+    pub fn fragments_within(
+        data: &[u8],
+        max_message_size: u32,
+    ) -> Result<PacketFragments<'_>, PacketError> {
+        let max = usize::try_from(max_message_size).unwrap_or(usize::MAX);
+
+        if data.len() > max {
+            return Err(PacketError::MessageTooLong {
+                len: data.len(),
+                max,
+            });
+        }
+
+        Ok(Self::fragments(data))
+    }
+}
+
+// The payload length must fit in the low six bits of the header without
+// touching the kind bits.
+const _: () = assert!(Packet::MAX_LEN == 0b00111111);
+
+/// Mutable packet view into a byte slice.
+///
+/// The payload length stored in the header is always at most
+/// [`Packet::MAX_LEN`] and never extends past the end of the backing buffer.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::{PacketKind, PacketMut};
+/// let mut buf = [0x83, 0x01, 0x02, 0x03];
+/// let mut packet = PacketMut::from_bytes(&mut buf);
+/// packet.set_kind(PacketKind::StdErr);
+/// assert_eq!(packet.kind(), PacketKind::StdErr);
+/// assert_eq!(packet.data(), &[0x01, 0x02, 0x03]);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct PacketMut<'a>(&'a mut [u8]);
+
+impl<'a> PacketMut<'a> {
+    /// Create a new mutable packet from a buffer.
+    ///
+    /// Only the first 64 bytes of a larger `buf` are used.
+    ///
+    /// Panics if `buf` is less than 1 byte in size or shorter than the length
+    /// declared in the header.
+    pub fn from_bytes(buf: &'a mut [u8]) -> Self {
+        assert!(!buf.is_empty());
+        let end = buf.len().min(Packet::REPORT_LEN);
+
+        let packet = Self(&mut buf[..end]);
+        assert!(packet.len() <= packet.0.len());
+        packet
+    }
+
+    /// Create a new empty packet, reserving the header byte in `buf`.
+    ///
+    /// Write the payload through [`PacketMut::payload_mut`] then commit its
+    /// length with [`PacketMut::set_len`].
+    ///
+    /// Only the first 64 bytes of a larger `buf` are used.
+    ///
+    /// Panics if `buf` is less than 1 byte in size.
+    pub fn new(buf: &'a mut [u8], kind: PacketKind) -> Self {
+        assert!(!buf.is_empty());
+        let end = buf.len().min(Packet::REPORT_LEN);
+
+        buf[0] = kind as u8;
+        Self(&mut buf[..end])
+    }
+
+    /// Returns the length of the packet *including* the header byte.
+    pub fn len(&self) -> usize {
+        self.payload_len() + wire::HEADER_LEN
+    }
+
+    /// Returns the length of the payload as declared in the header.
+    pub fn payload_len(&self) -> usize {
+        self.0[0] as usize & 0b00111111
+    }
+
+    /// Returns `true` if the packet has no payload.
+    pub fn is_empty(&self) -> bool {
+        self.payload_len() == 0
+    }
+
+    /// Get the kind of packet.
+    pub fn kind(&self) -> PacketKind {
+        PacketKind::from(self.0[0])
+    }
+
+    /// Set the kind of packet.
+    ///
+    /// Only the kind bits of the header are changed, the length is preserved.
+    pub fn set_kind(&mut self, kind: PacketKind) {
+        self.0[0] = (self.0[0] & !PacketKind::MASK) | kind.as_u8();
+    }
+
+    /// Set the payload length.
+    ///
+    /// Only the length bits of the header are changed, the kind is preserved.
+    ///
+    /// Panics if `len` is larger than [`Packet::MAX_LEN`] or the payload
+    /// would not fit in the backing buffer.
+    pub fn set_len(&mut self, len: usize) {
+        assert!(len <= Packet::MAX_LEN);
+        assert!(len < self.0.len());
+
+        self.0[0] = (self.0[0] & PacketKind::MASK) | len as u8;
+    }
+
+    /// Access the packet data.
+    pub fn data(&self) -> &[u8] {
+        &self.0[1..self.len()]
+    }
+
+    /// Mutably access the packet data.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        let len = self.len();
+        &mut self.0[1..len]
+    }
+
+    /// Mutably access the whole payload region of the backing buffer,
+    /// regardless of the current length.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.0[1..]
+    }
+
+    /// Borrow as an immutable [`Packet`].
+    pub fn as_packet(&self) -> Packet<'_> {
+        Packet(&self.0[..self.len()])
+    }
+
+    /// Convert into an immutable [`Packet`].
+    pub fn freeze(self) -> Packet<'a> {
+        let len = self.len();
+        Packet(&self.0[..len])
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Distance between the start of consecutive packets in a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Stride {
+    /// Packets are padded to [`Packet::REPORT_LEN`] bytes.
+    Report,
+    /// Packets are padded to a negotiated report size.
+    Sized(usize),
+    /// Packets follow each other with no padding.
+    Packed,
+}
 
-    /// Taken from HF2 spec.
-    const TEST_PACKET: &[&[u8]] = &[
-        &[0x83, 0x01, 0x02, 0x03, 0xAB, 0xFF, 0xFF, 0xFF],
-        &[0x85, 0x04, 0x05, 0x06, 0x07, 0x08],
-        &[0x80, 0xDE, 0x42, 0x42, 0x42, 0x42, 0xFF, 0xFF],
-        &[
-            0xD0, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15,
-            0x16, 0x17, 0xFF, 0xFF, 0xFF,
-        ],
-    ];
+/// Packet iterator over a byte stream.
+///
+/// Stops when the buffer is exhausted or the next packet header claims more
+/// bytes than remain.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::{Packet, PacketIter, PacketKind, Stride};
+/// let buf = [0x82, 0x01, 0x02, 0xC1, 0x03];
+/// let mut iter = PacketIter::new(&buf, Stride::Packed);
+/// assert_eq!(iter.next().unwrap().data(), &[0x01, 0x02]);
+/// assert_eq!(iter.next().unwrap().kind(), PacketKind::StdErr);
+/// assert!(iter.next().is_none());
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct PacketIter<'a> {
+    buf: &'a [u8],
+    stride: Stride,
+}
 
-    #[test]
-    fn test_packet() {
-        let packet = Packet(&[0, 0xFF, 0xFF]);
-        assert!(packet.kind() == PacketKind::CommandInner);
-        let packet = Packet(&[0x40, 0xFF, 0xFF]);
-        assert!(packet.kind() == PacketKind::CommandFinal);
-        let packet = Packet(&[0x80, 0xFF, 0xFF]);
-        assert!(packet.kind() == PacketKind::StdOut);
-        let packet = Packet(&[0xC0, 0xFF, 0xFF]);
-        assert!(packet.kind() == PacketKind::StdErr);
+impl<'a> PacketIter<'a> {
+    /// Create a new packet iterator over `buf`.
+    pub fn new(buf: &'a [u8], stride: Stride) -> Self {
+        Self { buf, stride }
+    }
+
+    /// Only yield packets of the given kind.
+    pub fn filter_kind(self, kind: PacketKind) -> FilterKind<'a> {
+        FilterKind { iter: self, kind }
+    }
+}
+
+impl<'a> Iterator for PacketIter<'a> {
+    type Item = Packet<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let header = *self.buf.first()?;
+        let len = (header as usize & 0b00111111) + wire::HEADER_LEN;
+
+        let stride = match self.stride {
+            Stride::Report => Packet::REPORT_LEN,
+            Stride::Sized(report_len) => report_len,
+            Stride::Packed => len,
+        };
+
+        // truncated packet or packet overrunning its report
+        if len > self.buf.len() || len > stride {
+            self.buf = &[];
+            return None;
+        }
+
+        let packet = Packet(&self.buf[..len]);
+
+        let advance = stride.min(self.buf.len());
+        self.buf = &self.buf[advance..];
+
+        Some(packet)
+    }
+}
+
+/// Packet iterator yielding only packets of a single kind.
+///
+/// See [`PacketIter::filter_kind`].
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::{Packet, PacketIter, PacketKind, Stride};
+/// let buf = [0x82, 0x01, 0x02, 0x41, 0xAA, 0x81, 0x03];
+/// let mut iter = PacketIter::new(&buf, Stride::Packed).filter_kind(PacketKind::StdOut);
+/// assert_eq!(iter.next().unwrap().data(), &[0x01, 0x02]);
+/// assert_eq!(iter.next().unwrap().data(), &[0x03]);
+/// assert!(iter.next().is_none());
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct FilterKind<'a> {
+    iter: PacketIter<'a>,
+    kind: PacketKind,
+}
+
+impl<'a> Iterator for FilterKind<'a> {
+    type Item = Packet<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().find(|packet| packet.kind() == self.kind)
+    }
+}
+
+/// Packet fragment iterator.
+///
+/// Yields [`Packet::MAX_LEN`] sized chunks of the payload as
+/// [`PacketKind::CommandInner`] packets followed by the last chunk as a
+/// [`PacketKind::CommandFinal`] packet. An empty payload yields a single empty
+/// [`PacketKind::CommandFinal`] packet.
+///
+/// Doesn't implement the [`Iterator`] trait because of some no-allocation
+/// constraints.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::{Packet, PacketKind};
+/// let data = [0x55; 100];
+/// let mut fragments = Packet::fragments(&data);
+///
+/// let mut buf = [0; 64];
+/// let packet = fragments.next(&mut buf).unwrap();
+/// assert_eq!(packet.kind(), PacketKind::CommandInner);
+/// assert_eq!(packet.data().len(), 63);
+///
+/// let packet = fragments.next(&mut buf).unwrap();
+/// assert_eq!(packet.kind(), PacketKind::CommandFinal);
+/// assert_eq!(packet.data().len(), 37);
+///
+/// assert!(fragments.next(&mut buf).is_none());
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct PacketFragments<'a> {
+    data: &'a [u8],
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> PacketFragments<'a> {
+    /// Get the next packet using `buf` to store the packet data.
+    ///
+    /// `buf` must be large enough to fit a full packet.
+    pub fn next<'b>(&mut self, buf: &'b mut [u8]) -> Option<Packet<'b>> {
+        if self.done {
+            return None;
+        }
+
+        let remaining = &self.data[self.offset..];
+        let len = remaining.len().min(Packet::MAX_LEN);
+
+        let kind = if remaining.len() <= Packet::MAX_LEN {
+            self.done = true;
+            PacketKind::CommandFinal
+        } else {
+            PacketKind::CommandInner
+        };
+
+        // advance to next chunk
+        self.offset += len;
+
+        Some(Packet::new(buf, kind, &remaining[..len]))
+    }
+}
+
+/// Build a [`Packet`] at compile time.
+///
+/// The header byte is computed from the kind and the number of payload bytes.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::{packet, Packet, PacketKind};
+/// const PACKET: Packet<'static> = packet!(PacketKind::StdOut, [b'o', b'k']);
+/// assert_eq!(PACKET.as_bytes(), &[0x82, b'o', b'k']);
+/// ```
+#[macro_export]
+macro_rules! packet {
+    ($kind:expr, [$($byte:expr),* $(,)?]) => {{
+        const DATA: &[u8] = &[$($byte),*];
+        const BYTES: [u8; DATA.len() + 1] = {
+            assert!(DATA.len() <= $crate::Packet::MAX_LEN);
+
+            let mut bytes = [0; DATA.len() + 1];
+            bytes[0] = $crate::PacketKind::as_u8($kind) | DATA.len() as u8;
+
+            let mut i = 0;
+            while i < DATA.len() {
+                bytes[i + 1] = DATA[i];
+                i += 1;
+            }
+
+            bytes
+        };
+        $crate::Packet::from_bytes_const(&BYTES)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Taken from HF2 spec.
+    const TEST_PACKET: &[&[u8]] = &[
+        &[0x83, 0x01, 0x02, 0x03, 0xAB, 0xFF, 0xFF, 0xFF],
+        &[0x85, 0x04, 0x05, 0x06, 0x07, 0x08],
+        &[0x80, 0xDE, 0x42, 0x42, 0x42, 0x42, 0xFF, 0xFF],
+        &[
+            0xD0, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15,
+            0x16, 0x17, 0xFF, 0xFF, 0xFF,
+        ],
+    ];
+
+    #[test]
+    fn test_packet() {
+        let packet = Packet(&[0, 0xFF, 0xFF]);
+        assert!(packet.kind() == PacketKind::CommandInner);
+        let packet = Packet(&[0x40, 0xFF, 0xFF]);
+        assert!(packet.kind() == PacketKind::CommandFinal);
+        let packet = Packet(&[0x80, 0xFF, 0xFF]);
+        assert!(packet.kind() == PacketKind::StdOut);
+        let packet = Packet(&[0xC0, 0xFF, 0xFF]);
+        assert!(packet.kind() == PacketKind::StdErr);
+    }
+
+    #[test]
+    fn test_payload_len() {
+        let packet = Packet(&[0x83, 0x01, 0x02, 0x03]);
+        assert_eq!(packet.payload_len(), 3);
+        assert_eq!(packet.payload_len(), packet.data().len());
+        assert_eq!(packet.len(), 4);
+
+        let packet = Packet(&[0x40]);
+        assert_eq!(packet.payload_len(), 0);
+        assert_eq!(packet.len(), 1);
+
+        let mut buf = [0xFF; 64];
+        let mut packet = PacketMut::new(&mut buf, PacketKind::StdErr);
+        assert_eq!(packet.payload_len(), 0);
+        packet.set_len(Packet::MAX_LEN);
+        assert_eq!(packet.payload_len(), Packet::MAX_LEN);
+        assert_eq!(packet.len(), Packet::REPORT_LEN);
+    }
+
+    #[test]
+    fn test_is_empty() {
+        assert!(Packet::from_bytes(&[0x40]).is_empty());
+        assert!(!Packet::from_bytes(&[0x41, 0x00]).is_empty());
+
+        let mut buf = [0; 64];
+        let mut packet = PacketMut::new(&mut buf, PacketKind::StdOut);
+        assert!(packet.is_empty());
+        packet.set_len(1);
+        assert!(!packet.is_empty());
+    }
+
+    #[test]
+    fn test_header() {
+        let mut buf = [0; 64];
+        let packet = Packet::new(&mut buf, PacketKind::StdOut, &[1, 2, 3]);
+        assert_eq!(packet.header(), 0x83);
+
+        let packet = Packet::from_bytes(&[0x40]);
+        assert_eq!(packet.header(), 0x40);
+    }
+
+    #[test]
+    fn test_packet_eq() {
+        let padded = Packet(&[0x82, 0x01, 0x02, 0x00, 0x00, 0xFF]);
+        let unpadded = Packet(&[0x82, 0x01, 0x02]);
+        assert_eq!(padded, unpadded);
+
+        let other_kind = Packet(&[0xC2, 0x01, 0x02]);
+        assert_ne!(unpadded, other_kind);
+
+        let other_data = Packet(&[0x82, 0x01, 0x03]);
+        assert_ne!(unpadded, other_data);
+
+        let other_len = Packet(&[0x81, 0x01, 0x02]);
+        assert_ne!(unpadded, other_len);
+    }
+
+    #[test]
+    fn test_packet_mut_set_kind() {
+        let mut buf = [0xFF; 64];
+        Packet::new(&mut buf, PacketKind::StdOut, &[1, 2, 3, 4, 5]);
+
+        let mut packet = PacketMut::from_bytes(&mut buf);
+        assert!(packet.kind() == PacketKind::StdOut);
+
+        for (kind, expected) in [
+            (PacketKind::CommandInner, 0x00),
+            (PacketKind::CommandFinal, 0x40),
+            (PacketKind::StdErr, 0xC0),
+            (PacketKind::StdOut, 0x80),
+        ] {
+            packet.set_kind(kind);
+            assert_eq!(packet.0[0] & 0b11000000, expected);
+            assert_eq!(packet.0[0] & 0b00111111, 5);
+            assert_eq!(packet.len(), 6);
+            assert_eq!(packet.data(), &[1, 2, 3, 4, 5]);
+        }
+
+        packet.set_kind(PacketKind::StdErr);
+        let packet = packet.as_packet();
+        assert!(packet.kind() == PacketKind::StdErr);
+        assert_eq!(packet.as_bytes(), &[0xC5, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_packet_mut_set_len() {
+        let mut buf = [0; 64];
+        Packet::new(&mut buf, PacketKind::StdErr, &[1, 2, 3, 4, 5]);
+
+        let mut packet = PacketMut::from_bytes(&mut buf);
+        packet.set_len(2);
+        assert!(packet.kind() == PacketKind::StdErr);
+        assert_eq!(packet.len(), 3);
+        assert_eq!(packet.data(), &[1, 2]);
+
+        packet.data_mut().copy_from_slice(&[9, 8]);
+        packet.set_len(Packet::MAX_LEN);
+        assert!(packet.kind() == PacketKind::StdErr);
+        assert_eq!(packet.data().len(), Packet::MAX_LEN);
+        assert_eq!(&packet.data()[..5], &[9, 8, 3, 4, 5]);
+
+        packet.set_len(0);
+        let packet = packet.freeze();
+        assert!(packet.kind() == PacketKind::StdErr);
+        assert_eq!(packet.as_bytes(), &[0xC0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_packet_mut_set_len_too_long() {
+        let mut buf = [0x80, 0x00, 0x00];
+        let mut packet = PacketMut::from_bytes(&mut buf);
+        packet.set_len(3);
+    }
+
+    #[test]
+    fn test_max_len_mask() {
+        const _: () = assert!(Packet::MAX_LEN & 0b11000000 == 0);
+        assert_eq!(Packet::MAX_LEN, 0b00111111);
+
+        // every valid length leaves the kind bits untouched
+        let data = [0; Packet::MAX_LEN];
+        for len in 0..=Packet::MAX_LEN {
+            let mut buf = [0; 64];
+            let packet = Packet::new(&mut buf, PacketKind::StdErr, &data[..len]);
+            assert!(packet.kind() == PacketKind::StdErr);
+            assert_eq!(packet.data().len(), len);
+        }
+    }
+
+    #[test]
+    fn test_packet_mut_payload_mut() {
+        let mut buf = [0xFF; 64];
+        let mut packet = PacketMut::new(&mut buf, PacketKind::StdOut);
+        assert!(packet.kind() == PacketKind::StdOut);
+        assert_eq!(packet.data(), &[0u8; 0]);
+        assert_eq!(packet.payload_mut().len(), Packet::MAX_LEN);
+
+        packet.payload_mut()[..5].copy_from_slice(b"hello");
+        packet.set_len(5);
+        assert_eq!(packet.data(), b"hello");
+
+        packet.payload_mut()[5..7].copy_from_slice(b"!\n");
+        packet.set_len(7);
+        assert_eq!(packet.data(), b"hello!\n");
+
+        let packet = packet.freeze();
+        assert!(packet.kind() == PacketKind::StdOut);
+        assert_eq!(packet.as_bytes(), b"\x87hello!\n");
+
+        let mut buf = [0; 4];
+        let mut packet = PacketMut::new(&mut buf, PacketKind::StdErr);
+        assert_eq!(packet.payload_mut().len(), 3);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut buf = [0xFF; 64];
+        let data = [1, 2, 3, 4, 5];
+        let (packet, len) = Packet::write(&mut buf, PacketKind::StdOut, &data);
+        assert_eq!(len, data.len() + 1);
+        assert_eq!(packet.data(), &data);
+        assert_eq!(&buf[..len], &[0x85, 1, 2, 3, 4, 5]);
+
+        let mut buf = [0; 1];
+        let (_, len) = Packet::write(&mut buf, PacketKind::CommandFinal, &[]);
+        assert_eq!(len, 1);
+    }
+
+    #[test]
+    fn test_try_new() {
+        let mut buf = [0; 64];
+        let packet = Packet::try_new(&mut buf, PacketKind::StdOut, &[1, 2, 3]).unwrap();
+        assert!(packet.kind() == PacketKind::StdOut);
+        assert_eq!(packet.data(), &[1, 2, 3]);
+
+        let mut buf = [0; 128];
+        let data = [0; 64];
+        assert_eq!(
+            Packet::try_new(&mut buf, PacketKind::StdOut, &data).unwrap_err(),
+            PacketError::DataTooLong { len: 64 }
+        );
+
+        let mut buf = [0; 3];
+        assert_eq!(
+            Packet::try_new(&mut buf, PacketKind::StdOut, &[1, 2, 3]).unwrap_err(),
+            PacketError::BufferTooSmall { needed: 4, got: 3 }
+        );
+    }
+
+    #[test]
+    fn test_try_from_bytes() {
+        let packet = Packet::try_from_bytes(&[0x82, 0x01, 0x02, 0xFF]).unwrap();
+        assert!(packet.kind() == PacketKind::StdOut);
+        assert_eq!(packet.as_bytes(), &[0x82, 0x01, 0x02]);
+
+        assert_eq!(
+            Packet::try_from_bytes(&[]).unwrap_err(),
+            PacketError::BufferTooSmall { needed: 1, got: 0 }
+        );
+
+        assert_eq!(
+            Packet::try_from_bytes(&[0x3F, 0x01, 0x02]).unwrap_err(),
+            PacketError::Truncated {
+                len: 64,
+                available: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_over_claim() {
+        // header claims 40 bytes of payload in a 10 byte transfer
+        let mut buf = [0; 10];
+        buf[0] = 0x80 | 40;
+
+        assert_eq!(
+            Packet::try_from_bytes(&buf).unwrap_err(),
+            PacketError::Truncated {
+                len: 41,
+                available: 10
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_bytes_over_claim_panics() {
+        let mut buf = [0; 10];
+        buf[0] = 0x80 | 40;
+        Packet::from_bytes(&buf);
+    }
+
+    #[test]
+    fn test_try_data() {
+        let packet = Packet(&[0x83, 0x01, 0x02, 0x03]);
+        assert_eq!(packet.try_data(), Some(&[0x01, 0x02, 0x03][..]));
+
+        let packet = Packet(&[0x40]);
+        assert_eq!(packet.try_data(), Some(&[0u8; 0][..]));
+
+        // header claims 5 bytes of payload, only 2 are present
+        let packet = Packet(&[0x85, 0x01, 0x02]);
+        assert_eq!(packet.try_data(), None);
+    }
+
+    #[test]
+    fn test_from_bytes_large_arena() {
+        let mut arena = [0xFF; 256];
+        arena[..4].copy_from_slice(&[0x83, b'a', b'b', b'c']);
+
+        let packet = Packet::from_bytes(&arena);
+        assert_eq!(packet.kind(), PacketKind::StdOut);
+        assert_eq!(packet.data(), b"abc");
+        assert_eq!(packet.as_bytes().len(), 4);
+    }
+
+    #[test]
+    fn test_from_bytes_const_large_arena() {
+        const ARENA: [u8; 256] = {
+            let mut arena = [0xFF; 256];
+            arena[0] = 0xC2;
+            arena
+        };
+        const PACKET: Packet<'static> = Packet::from_bytes_const(&ARENA);
+        assert_eq!(PACKET.kind(), PacketKind::StdErr);
+        assert_eq!(PACKET.data(), &[0xFF, 0xFF]);
+        assert_eq!(PACKET.as_bytes().len(), 3);
+    }
+
+    #[test]
+    fn test_packet_mut_large_arena() {
+        let mut arena = [0; 256];
+        arena[..3].copy_from_slice(&[0x82, b'h', b'i']);
+
+        let mut packet = PacketMut::from_bytes(&mut arena);
+        assert_eq!(packet.data(), b"hi");
+        assert_eq!(packet.payload_mut().len(), Packet::MAX_LEN);
+        packet.set_len(Packet::MAX_LEN);
+        assert_eq!(packet.freeze().len(), Packet::REPORT_LEN);
+
+        let mut packet = PacketMut::new(&mut arena, PacketKind::StdOut);
+        assert_eq!(packet.payload_mut().len(), Packet::MAX_LEN);
+        packet.payload_mut()[0] = b'!';
+        packet.set_len(1);
+        assert_eq!(packet.as_packet().data(), b"!");
+    }
+
+    #[test]
+    fn test_from_bytes_const() {
+        const PACKET: Packet<'static> = Packet::from_bytes_const(&[0x42, 0x01, 0x02, 0xFF]);
+        assert!(PACKET.kind() == PacketKind::CommandFinal);
+        assert_eq!(PACKET.data(), &[0x01, 0x02]);
+        assert_eq!(PACKET.as_bytes(), &[0x42, 0x01, 0x02]);
+    }
+
+    #[test]
+    fn test_packet_macro() {
+        const RESET: Packet<'static> =
+            packet!(PacketKind::CommandFinal, [0x03, 0, 0, 0, 0x01, 0x00, 0, 0]);
+        assert!(RESET.kind() == PacketKind::CommandFinal);
+        assert_eq!(RESET.data(), &[0x03, 0, 0, 0, 0x01, 0x00, 0, 0]);
+        assert_eq!(RESET.as_bytes()[0], 0x48);
+
+        let empty = packet!(PacketKind::StdErr, []);
+        assert!(empty.kind() == PacketKind::StdErr);
+        assert_eq!(empty.data(), &[0u8; 0]);
+    }
+
+    #[test]
+    fn test_empty_final() {
+        let packet = Packet::from_bytes(&[0x40, 0xFF, 0xFF]);
+        assert!(packet.kind() == PacketKind::CommandFinal);
+        assert!(packet.is_empty());
+        assert_eq!(packet.data(), &[0u8; 0]);
+        assert_eq!(packet.as_bytes(), &[0x40]);
+        assert_eq!(packet.validate(), Ok(()));
+
+        let packet = Packet::try_from_bytes(&[0x40]).unwrap();
+        assert!(packet.kind() == PacketKind::CommandFinal);
+        assert_eq!(packet.payload_len(), 0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Packet(&[0x82, 0x01, 0x02]).validate(), Ok(()));
+        assert_eq!(
+            Packet(&[0x85, 0x01, 0x02]).validate(),
+            Err(PacketError::Truncated {
+                len: 6,
+                available: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_as_bytes() {
+        let mut buf = [0xFF; 64];
+        let packet = Packet::new(&mut buf, PacketKind::CommandFinal, &[1, 2, 3]);
+        assert_eq!(packet.as_bytes(), &[0x43, 1, 2, 3]);
+
+        let parsed = Packet::from_bytes(packet.as_bytes());
+        assert!(parsed.kind() == PacketKind::CommandFinal);
+        assert_eq!(parsed.data(), packet.data());
+        assert_eq!(parsed.as_bytes(), packet.as_bytes());
+    }
+
+    #[test]
+    fn test_write_hex() {
+        let packet = Packet::from_bytes(&[0x83, 0x01, 0xAB, 0xFF]);
+
+        let mut out = [0; 8];
+        assert_eq!(packet.write_hex(&mut out), Ok(8));
+        assert_eq!(&out, b"8301ABFF");
+
+        let mut out = [b'-'; 10];
+        assert_eq!(packet.write_hex(&mut out), Ok(8));
+        assert_eq!(&out, b"8301ABFF--");
+
+        let mut out = [0; 7];
+        assert_eq!(
+            packet.write_hex(&mut out),
+            Err(PacketError::BufferTooSmall { needed: 8, got: 7 })
+        );
+    }
+
+    #[test]
+    fn test_write_report() {
+        let mut buf = [0; 64];
+        let packet = Packet::new(&mut buf, PacketKind::StdErr, &[1, 2, 3, 4]);
+
+        let mut report = [0xFF; Packet::REPORT_LEN];
+        assert_eq!(packet.write_report(&mut report), 5);
+        assert_eq!(&report[..5], &[0xC4, 1, 2, 3, 4]);
+        assert!(report[5..].iter().all(|&b| b == 0));
+
+        let parsed = Packet::from_bytes(&report);
+        assert!(parsed.kind() == PacketKind::StdErr);
+        assert_eq!(parsed.len(), 5);
+        assert_eq!(parsed.data(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_report_id() {
+        let mut buf = [0; 64];
+        let packet = Packet::new(&mut buf, PacketKind::StdOut, &[1, 2, 3]);
+
+        let mut report = [0xFF; Packet::REPORT_LEN + 1];
+        assert_eq!(packet.write_report_with_id(&mut report, 0x00), 5);
+        assert_eq!(&report[..5], &[0x00, 0x83, 1, 2, 3]);
+        assert!(report[5..].iter().all(|&b| b == 0));
+
+        let parsed = Packet::from_report(&report, true);
+        assert_eq!(parsed, packet);
+
+        // without a report ID the packet starts at the first byte
+        let mut report = [0xFF; Packet::REPORT_LEN];
+        packet.write_report(&mut report);
+        let parsed = Packet::from_report(&report, false);
+        assert_eq!(parsed, packet);
+    }
+
+    #[test]
+    fn test_iter_packed() {
+        let buf = [0x83, 0x01, 0x02, 0x03, 0x40, 0xC1, 0x04, 0x01];
+        let mut iter = PacketIter::new(&buf, Stride::Packed);
+
+        let packet = iter.next().unwrap();
+        assert!(packet.kind() == PacketKind::StdOut);
+        assert_eq!(packet.data(), &[0x01, 0x02, 0x03]);
+
+        let packet = iter.next().unwrap();
+        assert!(packet.kind() == PacketKind::CommandFinal);
+        assert_eq!(packet.data(), &[0u8; 0]);
+
+        let packet = iter.next().unwrap();
+        assert!(packet.kind() == PacketKind::StdErr);
+        assert_eq!(packet.data(), &[0x04]);
+
+        // truncated trailing packet
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_iter_filter_kind() {
+        let buf = [
+            0x02, 0x01, 0x02, // command inner
+            0x81, b'a', // stdout
+            0xC1, b'e', // stderr
+            0x41, 0x03, // command final
+            0x82, b'b', b'c', // stdout
+        ];
+
+        // kind header bits and the expected number of packets
+        for (bits, count) in [(0x00, 1), (0x40, 1), (0x80, 2), (0xC0, 1)] {
+            let iter = PacketIter::new(&buf, Stride::Packed).filter_kind(PacketKind::from(bits));
+
+            let mut n = 0;
+            for packet in iter {
+                assert!(packet.kind() == PacketKind::from(bits));
+                n += 1;
+            }
+            assert_eq!(n, count);
+        }
+
+        let mut stdout = PacketIter::new(&buf, Stride::Packed).filter_kind(PacketKind::StdOut);
+        assert_eq!(stdout.next().unwrap().data(), b"a");
+        assert_eq!(stdout.next().unwrap().data(), b"bc");
+        assert!(stdout.next().is_none());
+    }
+
+    #[test]
+    fn test_iter_report() {
+        let mut buf = [0xFF; Packet::REPORT_LEN * 3];
+        buf[0..3].copy_from_slice(&[0x82, 0x01, 0x02]);
+        buf[64..68].copy_from_slice(&[0x03, 0x03, 0x04, 0x05]);
+        buf[128..130].copy_from_slice(&[0x41, 0x06]);
+
+        let mut iter = Packet::iter(&buf);
+
+        let packet = iter.next().unwrap();
+        assert!(packet.kind() == PacketKind::StdOut);
+        assert_eq!(packet.data(), &[0x01, 0x02]);
+
+        let packet = iter.next().unwrap();
+        assert!(packet.kind() == PacketKind::CommandInner);
+        assert_eq!(packet.data(), &[0x03, 0x04, 0x05]);
+
+        let packet = iter.next().unwrap();
+        assert!(packet.kind() == PacketKind::CommandFinal);
+        assert_eq!(packet.data(), &[0x06]);
+
+        assert!(iter.next().is_none());
+
+        // short final report
+        assert_eq!(Packet::iter(&buf[..130]).count(), 3);
+    }
+
+    #[test]
+    fn test_report_sized() {
+        for report_len in [32, 64] {
+            let mut buf = [0; 64];
+            let data = [0xA5; 31];
+            let packet = Packet::new(&mut buf, PacketKind::StdOut, &data);
+
+            let mut report = [0xFF; 64];
+            let report = &mut report[..report_len];
+            assert_eq!(packet.write_report_sized(report), 32);
+            assert!(report[32..].iter().all(|&b| b == 0));
+
+            let parsed = Packet::from_bytes_sized(report, report_len);
+            assert_eq!(parsed, packet);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_bytes_sized_too_large() {
+        Packet::from_bytes_sized(&[0x80; 64], 32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_write_report_sized_too_small() {
+        let mut buf = [0; 64];
+        let packet = Packet::new(&mut buf, PacketKind::StdOut, &[0; 40]);
+        packet.write_report_sized(&mut [0; 32]);
+    }
+
+    #[test]
+    fn test_iter_sized() {
+        let mut buf = [0; 32 * 3];
+        buf[0..3].copy_from_slice(&[0x82, 0x01, 0x02]);
+        buf[32..34].copy_from_slice(&[0x41, 0x03]);
+        buf[64..66].copy_from_slice(&[0xC1, 0x04]);
+
+        let mut iter = Packet::iter_sized(&buf, 32);
+        assert_eq!(iter.next().unwrap().data(), &[0x01, 0x02]);
+        assert_eq!(iter.next().unwrap().data(), &[0x03]);
+        assert_eq!(iter.next().unwrap().data(), &[0x04]);
+        assert!(iter.next().is_none());
+
+        // header claiming more than the report size
+        let mut buf = [0; 64];
+        buf[0] = 0x3F;
+        assert!(Packet::iter_sized(&buf, 32).next().is_none());
+        assert!(Packet::iter(&buf).next().is_some());
+    }
+
+    #[test]
+    fn test_packets_for() {
+        assert_eq!(packets_for(0), 1);
+        assert_eq!(packets_for(1), 1);
+        assert_eq!(packets_for(63), 1);
+        assert_eq!(packets_for(64), 2);
+        assert_eq!(packets_for(126), 2);
+        assert_eq!(packets_for(127), 3);
+
+        assert_eq!(wire_size(0), 64);
+        assert_eq!(wire_size(63), 64);
+        assert_eq!(wire_size(64), 128);
+        assert_eq!(wire_size(126), 128);
+
+        // agrees with fragmentation
+        for len in [0, 63, 64, 126, 200] {
+            let data = [0; 200];
+            let mut fragments = Packet::fragments(&data[..len]);
+            let mut buf = [0; 64];
+            let mut count = 0;
+            while fragments.next(&mut buf).is_some() {
+                count += 1;
+            }
+            assert_eq!(count, packets_for(len));
+        }
+    }
+
+    #[test]
+    fn test_fragments() {
+        let mut buf = [0; 64];
+
+        // empty payload
+        let mut fragments = Packet::fragments(&[]);
+        let packet = fragments.next(&mut buf).unwrap();
+        assert!(packet.kind() == PacketKind::CommandFinal);
+        assert_eq!(packet.data().len(), 0);
+        assert!(fragments.next(&mut buf).is_none());
+
+        // single packet
+        let data = [0x55; 10];
+        let mut fragments = Packet::fragments(&data);
+        let packet = fragments.next(&mut buf).unwrap();
+        assert!(packet.kind() == PacketKind::CommandFinal);
+        assert_eq!(packet.data(), &data);
+        assert!(fragments.next(&mut buf).is_none());
+
+        // exact multiple of max length
+        let data = [0x55; Packet::MAX_LEN * 2];
+        let mut fragments = Packet::fragments(&data);
+        let packet = fragments.next(&mut buf).unwrap();
+        assert!(packet.kind() == PacketKind::CommandInner);
+        assert_eq!(packet.data().len(), Packet::MAX_LEN);
+        let packet = fragments.next(&mut buf).unwrap();
+        assert!(packet.kind() == PacketKind::CommandFinal);
+        assert_eq!(packet.data().len(), Packet::MAX_LEN);
+        assert!(fragments.next(&mut buf).is_none());
+
+        // multiple packets with remainder
+        let data: [u8; 200] = core::array::from_fn(|i| i as u8);
+        let mut fragments = Packet::fragments(&data);
+        let mut kinds = [
+            PacketKind::StdErr,
+            PacketKind::StdErr,
+            PacketKind::StdErr,
+            PacketKind::StdErr,
+        ];
+        let mut out = [0; 200];
+        let mut offset = 0;
+        let mut count = 0;
+        while let Some(packet) = fragments.next(&mut buf) {
+            out[offset..][..packet.data().len()].copy_from_slice(packet.data());
+            offset += packet.data().len();
+            kinds[count] = packet.kind();
+            count += 1;
+        }
+        assert_eq!(count, 4);
+        assert_eq!(offset, 200);
+        assert_eq!(out, data);
+        assert_eq!(
+            kinds,
+            [
+                PacketKind::CommandInner,
+                PacketKind::CommandInner,
+                PacketKind::CommandInner,
+                PacketKind::CommandFinal,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fragments_within() {
+        let mut buf = [0; 64];
+        let data = [0x55; 100];
+
+        let mut fragments = Packet::fragments_within(&data, 100).unwrap();
+        assert!(fragments.next(&mut buf).unwrap().kind() == PacketKind::CommandInner);
+        assert!(fragments.next(&mut buf).unwrap().kind() == PacketKind::CommandFinal);
+        assert!(fragments.next(&mut buf).is_none());
+
+        assert!(Packet::fragments_within(&[], 0).is_ok());
+        assert_eq!(
+            Packet::fragments_within(&data, 64).unwrap_err(),
+            PacketError::MessageTooLong { len: 100, max: 64 }
+        );
+    }
+
+    #[test]
+    fn test_kind_const() {
+        const _: () = assert!(PacketKind::StdOut.as_u8() == 0x80);
+        const KIND: PacketKind = PacketKind::from_header(0xC5);
+        const HEADER: u8 = PacketKind::CommandFinal.as_u8() | 3;
+
+        assert!(KIND == PacketKind::StdErr);
+        assert_eq!(HEADER, 0x43);
+        assert_eq!(PacketKind::MASK, 0xC0);
+        assert!(PacketKind::from_header(0x3F) == PacketKind::CommandInner);
+        assert!(PacketKind::from_header(0x7F) == PacketKind::CommandFinal);
+    }
+
+    #[test]
+    fn test_kind_predicates() {
+        // (kind, is_command, is_final, is_output)
+        let cases = [
+            (PacketKind::CommandInner, true, false, false),
+            (PacketKind::CommandFinal, true, true, false),
+            (PacketKind::StdOut, false, false, true),
+            (PacketKind::StdErr, false, false, true),
+        ];
+
+        for (kind, command, last, output) in cases {
+            assert_eq!(kind.is_command(), command);
+            assert_eq!(kind.is_final(), last);
+            assert_eq!(kind.is_output(), output);
+        }
+    }
+
+    #[test]
+    fn test_kind_try_from_header() {
+        assert_eq!(PacketKind::try_from_header(0x83, 4), Ok(PacketKind::StdOut));
+        assert_eq!(
+            PacketKind::try_from_header(0x83, 64),
+            Ok(PacketKind::StdOut)
+        );
+        assert_eq!(
+            PacketKind::try_from_header(0x40, 1),
+            Ok(PacketKind::CommandFinal)
+        );
+        assert_eq!(
+            PacketKind::try_from_header(0x83, 3),
+            Err(PacketError::Truncated {
+                len: 4,
+                available: 3
+            })
+        );
+        assert_eq!(
+            PacketKind::try_from_header(0x3F, 0),
+            Err(PacketError::Truncated {
+                len: 64,
+                available: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_kind_all() {
+        let bytes = PacketKind::all().map(PacketKind::as_u8);
+        assert_eq!(bytes, [0x00, 0x40, 0x80, 0xC0]);
+
+        for kind in PacketKind::all() {
+            assert_eq!(PacketKind::from_header(kind.as_u8()), kind);
+            assert_eq!(kind.as_u8() & !PacketKind::MASK, 0);
+        }
+    }
+
+    #[test]
+    fn test_kind_copy() {
+        let kind = PacketKind::StdOut;
+        let copy = kind;
+        assert_eq!(kind.as_u8(), 0x80);
+        assert_eq!(copy, kind);
+        assert_eq!(copy, PacketKind::StdOut);
+    }
+
+    #[test]
+    fn test_kind_try_from_exact() {
+        assert_eq!(
+            PacketKind::try_from_exact(0x00),
+            Ok(PacketKind::CommandInner)
+        );
+        assert_eq!(
+            PacketKind::try_from_exact(0x40),
+            Ok(PacketKind::CommandFinal)
+        );
+        assert_eq!(PacketKind::try_from_exact(0x80), Ok(PacketKind::StdOut));
+        assert_eq!(PacketKind::try_from_exact(0xC0), Ok(PacketKind::StdErr));
+
+        for byte in [0x01, 0x3F, 0x41, 0x83, 0xFF] {
+            assert_eq!(PacketKind::try_from_exact(byte), Err(InvalidKind(byte)));
+        }
+    }
+
+    #[test]
+    fn test_kind_of() {
+        const _: () = assert!(matches!(kind_of(0xC3), PacketKind::StdErr));
+        assert!(kind_of(0xC3) == PacketKind::StdErr);
+        assert!(kind_of(0x00) == PacketKind::CommandInner);
+        assert!(kind_of(0x7F) == PacketKind::CommandFinal);
+        assert!(kind_of(0x80) == PacketKind::StdOut);
     }
 
     #[test]
@@ -146,7 +1607,7 @@ mod tests {
         assert!(packet.kind() == PacketKind::StdOut);
         assert_eq!(packet.len(), 1);
         assert_eq!(packet.data().len(), 0);
-        assert_eq!(packet.data(), &[]);
+        assert_eq!(packet.data(), &[0u8; 0]);
 
         let packet = Packet(TEST_PACKET[3]);
         assert!(packet.kind() == PacketKind::StdErr);
@@ -0,0 +1,167 @@
+use crate::{Packet, PacketKind};
+
+/// Output collection error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum OutputError {
+    /// Output does not fit in the backing buffer.
+    Overflow { needed: usize, capacity: usize },
+}
+
+/// Device output collector.
+///
+/// Appends the payloads of [`PacketKind::StdOut`] and [`PacketKind::StdErr`]
+/// packets into separate buffers. Command packets are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::Packet;
+/// # use hftwo::output::OutputCollector;
+/// let mut stdout = [0; 64];
+/// let mut stderr = [0; 64];
+/// let mut collector = OutputCollector::new(&mut stdout, &mut stderr);
+///
+/// collector.push(&Packet::from_bytes(&[0x82, b'h', b'i'])).unwrap();
+/// collector.push(&Packet::from_bytes(&[0xC1, b'!'])).unwrap();
+/// collector.push(&Packet::from_bytes(&[0x41, 0x00])).unwrap();
+///
+/// assert_eq!(collector.stdout(), b"hi");
+/// assert_eq!(collector.stderr(), b"!");
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct OutputCollector<'a> {
+    stdout: Sink<'a>,
+    stderr: Sink<'a>,
+}
+
+impl<'a> OutputCollector<'a> {
+    /// Create a new collector using `stdout` and `stderr` as backing stores.
+    pub fn new(stdout: &'a mut [u8], stderr: &'a mut [u8]) -> Self {
+        Self {
+            stdout: Sink::new(stdout),
+            stderr: Sink::new(stderr),
+        }
+    }
+
+    /// Push a packet.
+    ///
+    /// On overflow the packet is dropped and the output collected so far is
+    /// kept.
+    pub fn push(&mut self, packet: &Packet) -> Result<(), OutputError> {
+        match packet.kind() {
+            PacketKind::StdOut => self.stdout.extend(packet.data()),
+            PacketKind::StdErr => self.stderr.extend(packet.data()),
+            PacketKind::CommandInner | PacketKind::CommandFinal => Ok(()),
+        }
+    }
+
+    /// Output collected from [`PacketKind::StdOut`] packets.
+    pub fn stdout(&self) -> &[u8] {
+        self.stdout.as_slice()
+    }
+
+    /// Output collected from [`PacketKind::StdErr`] packets.
+    pub fn stderr(&self) -> &[u8] {
+        self.stderr.as_slice()
+    }
+
+    /// Discard all collected output.
+    pub fn clear(&mut self) {
+        self.stdout.len = 0;
+        self.stderr.len = 0;
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+struct Sink<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Sink<'a> {
+    fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    fn extend(&mut self, data: &[u8]) -> Result<(), OutputError> {
+        let needed = self.len + data.len();
+
+        if needed > self.buf.len() {
+            return Err(OutputError::Overflow {
+                needed,
+                capacity: self.buf.len(),
+            });
+        }
+
+        self.buf[self.len..needed].copy_from_slice(data);
+        self.len = needed;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_routing() {
+        let mut stdout = [0; 64];
+        let mut stderr = [0; 64];
+        let mut collector = OutputCollector::new(&mut stdout, &mut stderr);
+
+        let packets: &[&[u8]] = &[
+            &[0x83, b'o', b'n', b'e'],
+            &[0x02, 0xAA, 0xBB],
+            &[0xC3, b'e', b'r', b'r'],
+            &[0x81, b','],
+            &[0x41, 0xCC],
+            &[0x83, b't', b'w', b'o'],
+            &[0xC0],
+        ];
+
+        for bytes in packets {
+            collector.push(&Packet::from_bytes(bytes)).unwrap();
+        }
+
+        assert_eq!(collector.stdout(), b"one,two");
+        assert_eq!(collector.stderr(), b"err");
+
+        collector.clear();
+        assert_eq!(collector.stdout(), &[0u8; 0]);
+        assert_eq!(collector.stderr(), &[0u8; 0]);
+    }
+
+    #[test]
+    fn test_output_overflow() {
+        let mut stdout = [0; 4];
+        let mut stderr = [0; 0];
+        let mut collector = OutputCollector::new(&mut stdout, &mut stderr);
+
+        let packet = Packet::from_bytes(&[0x83, b'a', b'b', b'c']);
+        assert_eq!(collector.push(&packet), Ok(()));
+        assert_eq!(
+            collector.push(&packet),
+            Err(OutputError::Overflow {
+                needed: 6,
+                capacity: 4
+            })
+        );
+        assert_eq!(collector.stdout(), b"abc");
+
+        let packet = Packet::from_bytes(&[0xC1, b'x']);
+        assert_eq!(
+            collector.push(&packet),
+            Err(OutputError::Overflow {
+                needed: 1,
+                capacity: 0
+            })
+        );
+    }
+}
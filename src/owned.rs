@@ -0,0 +1,103 @@
+use crate::{Packet, PacketKind};
+use heapless::Vec;
+
+/// Owned packet.
+///
+/// Stores a copy of a [`Packet`] so it can outlive the buffer it was parsed
+/// from, e.g. in a [`heapless::spsc::Queue`].
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::{OwnedPacket, Packet, PacketKind};
+/// let owned = {
+///     let buf = [0x82, 0x01, 0x02];
+///     OwnedPacket::from_packet(&Packet::from_bytes(&buf))
+/// };
+/// assert_eq!(owned.kind(), PacketKind::StdOut);
+/// assert_eq!(owned.data(), &[0x01, 0x02]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedPacket(Vec<u8, 64>);
+
+impl OwnedPacket {
+    /// Copy a [`Packet`] into a new owned packet.
+    pub fn from_packet(packet: &Packet) -> Self {
+        // packets are never longer than 64 bytes
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(packet.as_bytes()).unwrap();
+        Self(bytes)
+    }
+
+    /// Borrow as a [`Packet`].
+    pub fn as_packet(&self) -> Packet<'_> {
+        Packet(&self.0)
+    }
+
+    /// Get the kind of packet.
+    pub fn kind(&self) -> PacketKind {
+        self.as_packet().kind()
+    }
+
+    /// Access the packet data.
+    pub fn data(&self) -> &[u8] {
+        &self.0[1..]
+    }
+}
+
+impl From<&Packet<'_>> for OwnedPacket {
+    fn from(value: &Packet) -> Self {
+        Self::from_packet(value)
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for OwnedPacket {
+    fn format(&self, f: defmt::Formatter) {
+        self.as_packet().format(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owned_packet() {
+        let mut buf = [0; 64];
+        let packet = Packet::new(&mut buf, PacketKind::StdErr, &[1, 2, 3]);
+
+        let owned = OwnedPacket::from_packet(&packet);
+        assert!(owned.kind() == PacketKind::StdErr);
+        assert_eq!(owned.data(), &[1, 2, 3]);
+
+        let borrowed = owned.as_packet();
+        assert!(borrowed.kind() == PacketKind::StdErr);
+        assert_eq!(borrowed.as_bytes(), packet.as_bytes());
+    }
+
+    #[test]
+    fn test_owned_packet_full() {
+        let mut buf = [0; 64];
+        let data = [0x55; Packet::MAX_LEN];
+        let packet = Packet::new(&mut buf, PacketKind::CommandInner, &data);
+
+        let owned = OwnedPacket::from(&packet);
+        assert_eq!(owned.data(), &data);
+        assert_eq!(owned.as_packet().len(), 64);
+    }
+
+    #[test]
+    fn test_owned_packet_queue() {
+        let mut queue: heapless::spsc::Queue<OwnedPacket, 4> = heapless::spsc::Queue::new();
+
+        let buf = [0x41, 0x09];
+        queue
+            .enqueue(OwnedPacket::from_packet(&Packet::from_bytes(&buf)))
+            .unwrap();
+
+        let owned = queue.dequeue().unwrap();
+        assert!(owned.kind() == PacketKind::CommandFinal);
+        assert_eq!(owned.data(), &[0x09]);
+    }
+}
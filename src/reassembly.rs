@@ -0,0 +1,225 @@
+use crate::{Packet, PacketKind};
+
+/// Reassembly error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ReassemblyError {
+    /// Packet is not a command packet.
+    UnexpectedKind,
+    /// Message does not fit in the backing buffer.
+    Overflow { needed: usize, capacity: usize },
+}
+
+/// Command message reassembler.
+///
+/// Collects the payloads of [`PacketKind::CommandInner`] packets until a
+/// [`PacketKind::CommandFinal`] packet completes the message.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::Packet;
+/// # use hftwo::reassembly::Reassembler;
+/// let mut storage = [0; 256];
+/// let mut reassembler = Reassembler::new(&mut storage);
+///
+/// let inner = Packet::from_bytes(&[0x02, 0x01, 0x02]);
+/// assert_eq!(reassembler.push(&inner), Ok(None));
+///
+/// let last = Packet::from_bytes(&[0x41, 0x03]);
+/// assert_eq!(reassembler.push(&last), Ok(Some(&[0x01, 0x02, 0x03][..])));
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Reassembler<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Reassembler<'a> {
+    /// Create a new reassembler using `buf` as the backing store.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Push a packet.
+    ///
+    /// Returns the full message once a [`PacketKind::CommandFinal`] packet
+    /// is pushed, after which the reassembler starts a new message. A final
+    /// packet with an empty payload still completes the message, which is
+    /// then whatever was collected so far and may be empty.
+    ///
+    /// On overflow the partial message is discarded.
+    pub fn push(&mut self, packet: &Packet) -> Result<Option<&[u8]>, ReassemblyError> {
+        let kind = packet.kind();
+
+        if kind != PacketKind::CommandInner && kind != PacketKind::CommandFinal {
+            return Err(ReassemblyError::UnexpectedKind);
+        }
+
+        let data = packet.data();
+        let needed = self.len + data.len();
+
+        if needed > self.buf.len() {
+            self.len = 0;
+            return Err(ReassemblyError::Overflow {
+                needed,
+                capacity: self.buf.len(),
+            });
+        }
+
+        self.buf[self.len..needed].copy_from_slice(data);
+        self.len = needed;
+
+        if kind == PacketKind::CommandFinal {
+            let len = self.len;
+            self.len = 0;
+            return Ok(Some(&self.buf[..len]));
+        }
+
+        Ok(None)
+    }
+
+    /// Push packets from an iterator until a message is complete.
+    ///
+    /// Returns the full message once a [`PacketKind::CommandFinal`] packet is
+    /// pushed, without pulling any further packets from `packets`. Pass
+    /// `iter.by_ref()` to keep the remaining packets. Returns `None` if the
+    /// iterator runs out first, keeping the partial message.
+    pub fn extend_from_packets<'p>(
+        &mut self,
+        packets: impl Iterator<Item = Packet<'p>>,
+    ) -> Result<Option<&[u8]>, ReassemblyError> {
+        for packet in packets {
+            if packet.kind() == PacketKind::CommandFinal {
+                return self.push(&packet);
+            }
+
+            self.push(&packet)?;
+        }
+
+        Ok(None)
+    }
+
+    /// Length of the partial message collected so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no partial message has been collected.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discard any partial message.
+    pub fn reset(&mut self) {
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PacketIter, Stride};
+
+    #[test]
+    fn test_reassembly() {
+        let data: [u8; 200] = core::array::from_fn(|i| i as u8);
+        let mut fragments = Packet::fragments(&data);
+
+        let mut storage = [0; 256];
+        let mut reassembler = Reassembler::new(&mut storage);
+
+        let mut buf = [0; 64];
+        let mut message = None;
+        while let Some(packet) = fragments.next(&mut buf) {
+            assert!(message.is_none());
+            if let Some(m) = reassembler.push(&packet).unwrap() {
+                let mut out = [0; 200];
+                out.copy_from_slice(m);
+                message = Some(out);
+            }
+        }
+
+        assert_eq!(message, Some(data));
+        assert!(reassembler.is_empty());
+    }
+
+    #[test]
+    fn test_extend_from_packets() {
+        let buf = [
+            0x02, 0x01, 0x02, // inner
+            0x02, 0x03, 0x04, // inner
+            0x41, 0x05, // final
+            0x81, b'x', // stdout, not pulled
+        ];
+
+        let mut storage = [0; 64];
+        let mut reassembler = Reassembler::new(&mut storage);
+
+        let mut packets = PacketIter::new(&buf, Stride::Packed);
+        assert_eq!(
+            reassembler.extend_from_packets(packets.by_ref()),
+            Ok(Some(&[0x01, 0x02, 0x03, 0x04, 0x05][..]))
+        );
+        assert_eq!(packets.next().unwrap().data(), b"x");
+        assert!(reassembler.is_empty());
+
+        // runs out before the final packet
+        let packets = PacketIter::new(&buf[..6], Stride::Packed);
+        assert_eq!(reassembler.extend_from_packets(packets), Ok(None));
+        assert_eq!(reassembler.len(), 4);
+    }
+
+    #[test]
+    fn test_reassembly_empty_final() {
+        let mut storage = [0; 64];
+        let mut reassembler = Reassembler::new(&mut storage);
+
+        let last = Packet::from_bytes(&[0x40]);
+        assert_eq!(reassembler.push(&last), Ok(Some(&[0u8; 0][..])));
+        assert!(reassembler.is_empty());
+
+        let inner = Packet::from_bytes(&[0x02, 0x01, 0x02]);
+        assert_eq!(reassembler.push(&inner), Ok(None));
+        assert_eq!(reassembler.push(&last), Ok(Some(&[0x01, 0x02][..])));
+        assert!(reassembler.is_empty());
+    }
+
+    #[test]
+    fn test_reassembly_output_rejected() {
+        let mut storage = [0; 64];
+        let mut reassembler = Reassembler::new(&mut storage);
+
+        let packet = Packet::from_bytes(&[0x81, 0x01]);
+        assert_eq!(
+            reassembler.push(&packet),
+            Err(ReassemblyError::UnexpectedKind)
+        );
+
+        let packet = Packet::from_bytes(&[0xC1, 0x01]);
+        assert_eq!(
+            reassembler.push(&packet),
+            Err(ReassemblyError::UnexpectedKind)
+        );
+    }
+
+    #[test]
+    fn test_reassembly_overflow() {
+        let mut storage = [0; 4];
+        let mut reassembler = Reassembler::new(&mut storage);
+
+        let packet = Packet::from_bytes(&[0x03, 0x01, 0x02, 0x03]);
+        assert_eq!(reassembler.push(&packet), Ok(None));
+        assert_eq!(reassembler.len(), 3);
+
+        assert_eq!(
+            reassembler.push(&packet),
+            Err(ReassemblyError::Overflow {
+                needed: 6,
+                capacity: 4
+            })
+        );
+        assert!(reassembler.is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+use crate::command::Response;
+
+/// Host-side tag allocator.
+///
+/// Hands out tags for pairing a [`Request`](crate::command::Request) with its
+/// [`Response`], wrapping around at [`u16::MAX`].
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::session::TagAllocator;
+/// let mut tags = TagAllocator::new();
+/// assert_eq!(tags.next(), 0);
+/// assert_eq!(tags.next(), 1);
+/// ```
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct TagAllocator {
+    next: u16,
+    reserved: Option<u16>,
+}
+
+impl TagAllocator {
+    /// Create a new tag allocator starting at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new tag allocator that never hands out `reserved`.
+    pub fn with_reserved(reserved: u16) -> Self {
+        Self {
+            next: 0,
+            reserved: Some(reserved),
+        }
+    }
+
+    /// Get the next tag.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> u16 {
+        if Some(self.next) == self.reserved {
+            self.next = self.next.wrapping_add(1);
+        }
+
+        let tag = self.next;
+        self.next = self.next.wrapping_add(1);
+        tag
+    }
+}
+
+/// Outstanding request tracker.
+///
+/// Holds up to `N` tags awaiting a response.
+///
+/// # Example
+///
+/// ```rust
+/// # use hftwo::command::{Response, Status};
+/// # use hftwo::session::Pending;
+/// let mut pending = Pending::<4>::new();
+/// assert!(pending.insert(7));
+///
+/// let mut buf = [0; 4];
+/// let response = Response::new(&mut buf, 7, Status::Success, 0, &[]);
+/// assert!(pending.complete(&response));
+/// assert!(!pending.complete(&response));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Pending<const N: usize> {
+    tags: [Option<u16>; N],
+}
+
+impl<const N: usize> Pending<N> {
+    /// Create a new empty tracker.
+    pub fn new() -> Self {
+        Self { tags: [None; N] }
+    }
+
+    /// Mark `tag` as outstanding.
+    ///
+    /// Returns `false` if the tracker is full or `tag` is already outstanding.
+    pub fn insert(&mut self, tag: u16) -> bool {
+        if self.contains(tag) {
+            return false;
+        }
+
+        match self.tags.iter_mut().find(|t| t.is_none()) {
+            Some(slot) => {
+                *slot = Some(tag);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if `tag` is outstanding.
+    pub fn contains(&self, tag: u16) -> bool {
+        self.tags.contains(&Some(tag))
+    }
+
+    /// Clear the outstanding request matching the tag of `response`.
+    ///
+    /// Returns `false` if no outstanding request matches.
+    pub fn complete(&mut self, response: &Response) -> bool {
+        self.remove(response.tag())
+    }
+
+    /// Clear `tag`.
+    ///
+    /// Returns `false` if `tag` was not outstanding.
+    pub fn remove(&mut self, tag: u16) -> bool {
+        match self.tags.iter_mut().find(|t| **t == Some(tag)) {
+            Some(slot) => {
+                *slot = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of outstanding requests.
+    pub fn len(&self) -> usize {
+        self.tags.iter().filter(|t| t.is_some()).count()
+    }
+
+    /// Returns `true` if there are no outstanding requests.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<const N: usize> Default for Pending<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Status;
+
+    #[test]
+    fn test_tag_allocator() {
+        let mut tags = TagAllocator::new();
+        assert_eq!(tags.next(), 0);
+        assert_eq!(tags.next(), 1);
+        assert_eq!(tags.next(), 2);
+    }
+
+    #[test]
+    fn test_tag_allocator_wraparound() {
+        let mut tags = TagAllocator {
+            next: u16::MAX - 1,
+            reserved: None,
+        };
+        assert_eq!(tags.next(), u16::MAX - 1);
+        assert_eq!(tags.next(), u16::MAX);
+        assert_eq!(tags.next(), 0);
+    }
+
+    #[test]
+    fn test_tag_allocator_reserved() {
+        let mut tags = TagAllocator::with_reserved(0);
+        assert_eq!(tags.next(), 1);
+
+        let mut tags = TagAllocator {
+            next: u16::MAX,
+            reserved: Some(0),
+        };
+        assert_eq!(tags.next(), u16::MAX);
+        assert_eq!(tags.next(), 1);
+    }
+
+    #[test]
+    fn test_pending() {
+        let mut pending = Pending::<2>::new();
+        assert!(pending.is_empty());
+
+        assert!(pending.insert(1));
+        assert!(!pending.insert(1));
+        assert!(pending.insert(2));
+        assert!(!pending.insert(3));
+        assert_eq!(pending.len(), 2);
+
+        let mut buf = [0; 4];
+        let response = Response::new(&mut buf, 2, Status::Success, 0, &[]);
+        assert!(pending.complete(&response));
+        assert!(!pending.contains(2));
+        assert!(pending.contains(1));
+
+        let mut buf = [0; 4];
+        let response = Response::new(&mut buf, 5, Status::Success, 0, &[]);
+        assert!(!pending.complete(&response));
+        assert_eq!(pending.len(), 1);
+    }
+}
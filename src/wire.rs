@@ -0,0 +1,25 @@
+//! Wire format sizes.
+
+/// Packet header length.
+pub const HEADER_LEN: usize = 1;
+
+/// Maximum packet payload length.
+pub const MAX_PAYLOAD: usize = 63;
+
+/// HID report length.
+pub const REPORT_LEN: usize = 64;
+
+const _: () = assert!(HEADER_LEN + MAX_PAYLOAD == REPORT_LEN);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Packet;
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(HEADER_LEN + MAX_PAYLOAD, REPORT_LEN);
+        assert_eq!(Packet::MAX_LEN, MAX_PAYLOAD);
+        assert_eq!(Packet::REPORT_LEN, REPORT_LEN);
+    }
+}
@@ -0,0 +1,76 @@
+//! Host and device exchanging messages through the public API only.
+
+use hftwo::codec::{Decoder, Encoder};
+use hftwo::command::{validate_request, Command, Request, Response, Status};
+use hftwo::reassembly::Reassembler;
+use hftwo::{packet, Packet, PacketKind};
+
+#[test]
+fn test_request_response_round_trip() {
+    let page: [u8; 100] = core::array::from_fn(|i| i as u8);
+
+    let mut body = [0; 104];
+    body[..4].copy_from_slice(&0x2000u32.to_le_bytes());
+    body[4..].copy_from_slice(&page);
+
+    // host encodes the request into reports
+    let mut reports = [0; 256];
+    let mut encoder = Encoder::new(&mut reports);
+    encoder
+        .push_command(Command::WriteFlashPage, 0x1234, &body)
+        .unwrap();
+
+    // device decodes and handles it
+    let mut scratch = [0; 256];
+    let mut stdout = [0; 64];
+    let mut stderr = [0; 64];
+    let mut decoder = Decoder::new(&mut scratch, &mut stdout, &mut stderr);
+
+    let mut message = None;
+    for report in encoder.as_bytes().chunks(Packet::REPORT_LEN) {
+        message = decoder.push_report(report).unwrap().map(|m| m.to_vec());
+    }
+
+    let message = message.unwrap();
+    let request = Request::from_bytes(&message);
+    assert_eq!(request.command(), Command::WriteFlashPage);
+    assert_eq!(request.tag(), 0x1234);
+    assert_eq!(request.data(), &body);
+    assert_eq!(validate_request(&request), Ok(()));
+
+    let mut expected = [0; 112];
+    let expected = Request::from_command(Command::WriteFlashPage, 0x1234, &body, &mut expected);
+    assert_eq!(request, expected);
+
+    // device replies, host reassembles
+    let mut buf = [0; 64];
+    let response = Response::reply_to(&mut buf, &request, Status::Success, 0, &[]);
+    let response_len = Response::HEADER_LEN + response.data().len();
+
+    let mut storage = [0; 64];
+    let mut reassembler = Reassembler::new(&mut storage);
+    let mut fragments = Packet::fragments(&buf[..response_len]);
+    let mut packet_buf = [0; 64];
+    let packet = fragments.next(&mut packet_buf).unwrap();
+    assert_eq!(packet.kind(), PacketKind::CommandFinal);
+
+    let message = reassembler.push(&packet).unwrap().unwrap();
+    let response = Response::from_bytes(message);
+    assert_eq!(response.expect_tag(0x1234).unwrap().result(), Ok(&[][..]));
+}
+
+#[test]
+fn test_output_packets() {
+    let mut buf = [0; 64];
+    let built = Packet::new(&mut buf, PacketKind::StdOut, b"hi");
+    const PACKET: Packet<'static> = packet!(PacketKind::StdOut, [b'h', b'i']);
+    assert_eq!(built, PACKET);
+
+    let mut scratch = [0; 16];
+    let mut stdout = [0; 16];
+    let mut stderr = [0; 16];
+    let mut decoder = Decoder::new(&mut scratch, &mut stdout, &mut stderr);
+
+    assert_eq!(decoder.push_report(PACKET.as_bytes()), Ok(None));
+    assert_eq!(decoder.output().stdout(), b"hi");
+}